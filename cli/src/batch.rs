@@ -0,0 +1,205 @@
+use crate::report::{CliError, Failure, Report, Summary};
+use glob::{glob_with, MatchOptions};
+use nanopub::error::NpError;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    thread,
+};
+use walkdir::{DirEntry, WalkDir};
+
+/// Extensions of the nanopub files looked for in directories
+const NP_EXTENSIONS: [&str; 3] = ["trig", "nq", "jsonld"];
+
+/// Check if a path is a glob pattern, e.g. `nanopubs/**/*.trig`
+pub fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Files to process in a batch, with the entries that could not be read while looking for them
+#[derive(Default)]
+pub struct BatchFiles {
+    pub files: Vec<PathBuf>,
+    /// Entries that could not be read, reported as failures of the batch
+    pub unreadable: Vec<(PathBuf, io::Error)>,
+}
+
+/// Get the nanopub files to process from the given paths.
+///
+/// Directories are walked recursively for `.trig`, `.nq`, and `.jsonld` files, and globs are matched against all files.
+/// Files and directories starting with a `.` are skipped, unless a glob names them, and symbolic links to directories are not followed
+pub fn expand_paths(paths: &[&String]) -> Result<BatchFiles, NpError> {
+    let mut batch = BatchFiles::default();
+    for path in paths {
+        if is_glob(path) {
+            let files_before = batch.files.len();
+            glob_files(path, &mut batch)?;
+            if batch.files.len() == files_before {
+                return Err(NpError(format!("No files matching {path}")));
+            }
+        } else if Path::new(path).is_dir() {
+            walk_dir(Path::new(path), &mut batch);
+        } else if Path::new(path).is_file() {
+            batch.files.push(PathBuf::from(path));
+        } else {
+            return Err(NpError(format!("No such file or directory: {path}")));
+        }
+    }
+    batch.files.sort();
+    batch.files.dedup();
+    Ok(batch)
+}
+
+/// Add the nanopub files of a directory and its sub-directories
+fn walk_dir(dir: &Path, batch: &mut BatchFiles) {
+    let is_hidden = |entry: &DirEntry| {
+        entry.depth() > 0 && entry.file_name().to_string_lossy().starts_with('.')
+    };
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| !is_hidden(entry))
+    {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let path = e.path().unwrap_or(dir).to_path_buf();
+                batch.unreadable.push((path, e.into()));
+                continue;
+            }
+        };
+        // Symbolic links to files are followed, the ones to directories are not to avoid loops
+        let is_file =
+            entry.file_type().is_file() || (entry.path_is_symlink() && entry.path().is_file());
+        let is_np_file = entry
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| NP_EXTENSIONS.contains(&ext));
+        if is_file && is_np_file {
+            batch.files.push(entry.into_path());
+        }
+    }
+}
+
+/// Add the files matching a glob pattern, supporting `*`, `**`, `?`, and `[...]`.
+///
+/// Wildcards do not match `/`, nor the files and directories starting with a `.`
+fn glob_files(pattern: &str, batch: &mut BatchFiles) -> Result<(), NpError> {
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+    let paths = glob_with(pattern, options)
+        .map_err(|e| NpError(format!("Invalid glob pattern {pattern}: {e}")))?;
+    for path in paths {
+        match path {
+            Ok(path) if path.is_file() => batch.files.push(path),
+            Ok(_) => {}
+            Err(e) => batch.unreadable.push((e.path().to_path_buf(), e.into())),
+        }
+    }
+    Ok(())
+}
+
+/// Process the files in parallel, the results are in the same order as the files
+pub fn process_files<F>(files: &[PathBuf], process: F) -> Vec<Result<Report, CliError>>
+where
+    F: Fn(&str) -> Result<Report, CliError> + Sync,
+{
+    let threads = thread::available_parallelism().map_or(4, |n| n.get());
+    let chunk_size = files.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let process = &process;
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|file| process(&file.to_string_lossy()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Processing thread panicked"))
+            .collect()
+    })
+}
+
+/// Print the failures to stderr and a summary table of the batch, returns an error if any file failed.
+///
+/// The report lists the result of each file, with the error for the ones that failed
+pub fn summarize(
+    command: &str,
+    batch: BatchFiles,
+    results: Vec<Result<Report, CliError>>,
+    quiet: bool,
+) -> Result<Report, CliError> {
+    let mut reports: Vec<Report> = Vec::with_capacity(results.len());
+    let mut failures: Vec<Failure> = Vec::new();
+    // The entries that could not be read are failures too
+    let unreadable = batch.unreadable.into_iter().map(|(path, e)| {
+        let error = CliError {
+            failure: Failure::Io,
+            message: format!("Error reading {}: {e}", path.display()),
+            report: None,
+        };
+        (path, Err(error))
+    });
+    for (file, result) in batch.files.into_iter().zip(results).chain(unreadable) {
+        let file = file.to_string_lossy().to_string();
+        let report = match result {
+            Ok(report) => report,
+            Err(e) => {
+                if !quiet {
+                    eprintln!("❌ {file}: {}", e.message);
+                }
+                failures.push(e.failure);
+                let mut report = e.report.map(|r| *r).unwrap_or_default();
+                report.command = command.to_string();
+                report.error = Some(e.message);
+                report.exit_code = e.failure as u8;
+                report
+            }
+        };
+        reports.push(Report {
+            source: Some(file),
+            ..report
+        });
+    }
+    let summary = Summary {
+        total: reports.len(),
+        passed: reports.len() - failures.len(),
+        failed: failures.len(),
+    };
+    if !quiet {
+        println!("\n📊 Summary");
+        println!("  ✅ Passed  {:>8}", summary.passed);
+        println!("  ❌ Failed  {:>8}", summary.failed);
+        println!("  📁 Total   {:>8}", summary.total);
+    }
+    let report = Report {
+        command: command.to_string(),
+        summary: Some(summary),
+        files: reports,
+        ..Default::default()
+    };
+    let Some(first) = failures.first() else {
+        return Ok(report);
+    };
+    // Exit with the code of the failure when all files failed for the same reason
+    let failure = if failures.iter().all(|f| f == first) {
+        *first
+    } else {
+        Failure::Error
+    };
+    Err(CliError {
+        failure,
+        message: format!("{} of {} files failed", summary.failed, summary.total),
+        report: Some(Box::new(report)),
+    })
+}
@@ -0,0 +1,120 @@
+use nanopub::{error::NpError, profile::default_profile_dir, utils::RdfFormat};
+use serde::{de, Deserialize, Deserializer};
+use std::{env, fs, path::PathBuf};
+
+/// Defaults of the CLI, from `~/.nanopub/config.toml`, overridden by the `NANOPUB_PROFILE`, `NANOPUB_KEY`,
+/// and `NANOPUB_SERVER` environment variables. The options given on the command line take precedence.
+///
+/// ```toml
+/// profile = "~/.nanopub/profile.yml"
+/// server = "https://np.knowledgepixels.com/"
+/// format = "jsonld"
+/// ```
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Path to the profile.yml used to sign
+    pub profile: Option<String>,
+    /// Path to the private key used to sign, used instead of the profile when both are set
+    pub key: Option<String>,
+    /// URL of the server where to publish, instead of a random server of the network
+    pub server: Option<String>,
+    /// RDF format of the nanopubs written by `fetch` and `convert`
+    #[serde(deserialize_with = "deserialize_format")]
+    pub format: Option<RdfFormat>,
+}
+
+impl Config {
+    /// Load the config file if it exists, and the environment variables
+    pub fn load() -> Result<Self, NpError> {
+        let path = config_path();
+        let file: Self = if path.is_file() {
+            toml::from_str(&fs::read_to_string(&path)?)
+                .map_err(|e| NpError(format!("Invalid config file {}: {e}", path.display())))?
+        } else {
+            Self::default()
+        };
+        Ok(Self::from_sources(file, |var| env::var(var).ok()))
+    }
+
+    /// Combine the config file with the environment variables, any variable set takes precedence over the file.
+    ///
+    /// The profile and the key are taken together from the variables when one of them is set,
+    /// so a key in the file is not used instead of the `NANOPUB_PROFILE` variable
+    fn from_sources(file: Self, env: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| env(name).filter(|value| !value.is_empty());
+        let (env_profile, env_key) = (var("NANOPUB_PROFILE"), var("NANOPUB_KEY"));
+        let (profile, key) = if env_profile.is_some() || env_key.is_some() {
+            (env_profile, env_key)
+        } else {
+            (file.profile, file.key)
+        };
+        Self {
+            profile: profile.map(expand_home),
+            key: key.map(expand_home),
+            server: var("NANOPUB_SERVER").or(file.server),
+            format: file.format,
+        }
+    }
+}
+
+/// Path to the config file: `~/.nanopub/config.toml`
+pub fn config_path() -> PathBuf {
+    default_profile_dir().join("config.toml")
+}
+
+/// Replace a leading `~/` by the home directory
+fn expand_home(path: String) -> String {
+    // The profile directory is in the home directory
+    let profile_dir = default_profile_dir();
+    match (path.strip_prefix("~/"), profile_dir.parent()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => path,
+    }
+}
+
+fn deserialize_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<RdfFormat>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|format| format.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file() -> Config {
+        toml::from_str(
+            "key = \"/file/id_rsa\"\nserver = \"https://file.example.org/\"\nformat = \"jsonld\"",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        // A profile in the environment is used instead of a key in the file
+        let config = Config::from_sources(file(), |var| match var {
+            "NANOPUB_PROFILE" => Some("/env/profile.yml".to_string()),
+            "NANOPUB_SERVER" => Some("https://env.example.org/".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.profile.as_deref(), Some("/env/profile.yml"));
+        assert_eq!(config.key, None);
+        assert_eq!(config.server.as_deref(), Some("https://env.example.org/"));
+        assert_eq!(config.format, Some(RdfFormat::JsonLd));
+
+        // The file is used when no variable is set, empty variables are ignored
+        let config = Config::from_sources(file(), |_| Some(String::new()));
+        assert_eq!(config.profile, None);
+        assert_eq!(config.key.as_deref(), Some("/file/id_rsa"));
+        assert_eq!(config.server.as_deref(), Some("https://file.example.org/"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(toml::from_str::<Config>("servers = \"https://example.org/\"").is_err());
+        assert!(toml::from_str::<Config>("format = \"xml\"").is_err());
+    }
+}
@@ -1,52 +1,300 @@
-use clap::{arg, value_parser, Command};
+use batch::{expand_paths, is_glob, process_files, summarize};
+use clap::{arg, error::ErrorKind, value_parser, ArgAction, ArgMatches, Command};
 use clap_complete::{generate, Generator, Shell};
-use nanopub::{error::NpError, get_np_server, Nanopub, ProfileBuilder};
-use std::{error::Error, fs, io, path::Path};
+use config::Config;
+use nanopub::{
+    diff::NpDiff,
+    error::NpError,
+    get_np_server,
+    nanopub::NpPart,
+    profile::{default_profile_dir, gen_keys},
+    servers::init_np_servers,
+    template::Template,
+    utils::{extract_artifact_code, parse_rdf, RdfFormat},
+    Nanopub, NanopubStore, NpProfile, ProfileBuilder,
+};
+use report::{CliError, Failure, GraphChanges, GraphReport, OrFail, ProfileReport, Report};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+mod batch;
+mod config;
+mod report;
+
+const BOLD: &str = "\x1b[1;96m";
+const END: &str = "\x1b[0m";
+/// Path used for stdin or stdout
+const STDIO: &str = "-";
+
+/// Print a message for humans, skipped when the output is JSON
+macro_rules! status {
+    ($json:expr, $($arg:tt)*) => {
+        if !$json {
+            println!($($arg)*);
+        }
+    };
+}
 
 // https://github.com/clap-rs/clap/blob/master/examples/git.rs
 // cargo run -- sign tests/resources/nanopub_test_blank.trig -k tests/resources/id_rsa
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() -> ExitCode {
     let mut cmd = Command::new("nanopub")
         .bin_name("np")
         .version(env!("CARGO_PKG_VERSION"))
         .about("Sign, publish, and check Nanopublications.")
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .arg(
+            arg!(--"cache-dir" <CACHE_DIR> "Directory where trusty nanopubs are cached, to avoid fetching them again")
+                .global(true)
+        )
+        .arg(
+            arg!(--json "Print the result as JSON, for the sign, check, publish, and fetch commands")
+                .global(true)
+        )
         .subcommand(
             Command::new("sign")
                 .about("Sign a Nanopub")
-                .arg(arg!(<NANOPUB_FILE> "The file to sign"))
+                .arg(arg!(<NANOPUB_FILES>... "The files, directories, or globs of the files to sign, or - to read from stdin"))
                 .arg(
-                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign. Found in ~/.nanopub by default")
+                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign. Default: NANOPUB_KEY")
                         .default_value("")
                 )
                 .arg(
-                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: ~/.nanopub/profile.yml")
+                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
                         .default_value("")
                 )
+                .arg(
+                    arg!(-o --output <OUTPUT_FILE> "File where to write the signed Nanopub, or - for stdout. Default: signed.<NANOPUB_FILE>, or stdout when reading from stdin")
+                )
+                .arg(
+                    arg!(-i --"in-place" "Overwrite the input files with the signed Nanopubs")
+                        .conflicts_with("output")
+                )
                 .arg_required_else_help(true),
         )
         .subcommand(
             Command::new("publish")
                 .about("Sign, publish, or check a Nanopublication (https://nanopub.net)")
-                .arg(arg!(<NANOPUB_FILE> "The file to publish"))
+                .arg(arg!(<NANOPUB_FILE> "The file to publish, or - to read from stdin"))
+                .arg(
+                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign. Default: NANOPUB_KEY")
+                        .default_value("")
+                )
+                .arg(
+                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
+                        .default_value("")
+                )
+                .arg(
+                    arg!(-t --test "To publish to the test server instead of the Nanopublication network.")
+                )
+                .arg(
+                    arg!(--server <URL> "URL of the server where to publish. Default: NANOPUB_SERVER, or a random server of the network")
+                        .conflicts_with("test")
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("unsign")
+                .about("Remove the signature of a Nanopub, and put it back in the temporary namespace")
+                .arg(arg!(<NANOPUB_FILE> "The file to unsign, or - to read from stdin"))
+                .arg(
+                    arg!(-o --output <OUTPUT_FILE> "File where to write the unsigned Nanopub, or - for stdout. Default: unsigned.<NANOPUB_FILE>, or stdout when reading from stdin")
+                )
+                .arg(
+                    arg!(-i --"in-place" "Overwrite the input file with the unsigned Nanopub")
+                        .conflicts_with("output")
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("intro")
+                .about("Sign and publish an introduction Nanopub, declaring the public key of your profile for your ORCID")
                 .arg(
-                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign.")
+                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign. Default: NANOPUB_KEY")
                         .default_value("")
                 )
                 .arg(
-                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: ~/.nanopub/profile.yml")
+                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
                         .default_value("")
                 )
                 .arg(
                     arg!(-t --test "To publish to the test server instead of the Nanopublication network.")
                 )
+                .arg(
+                    arg!(--server <URL> "URL of the server where to publish. Default: NANOPUB_SERVER, or a random server of the network")
+                        .conflicts_with("test")
+                )
+                .arg(
+                    arg!(-o --output <OUTPUT_FILE> "Write the signed introduction to a file instead of publishing it")
+                ),
+        )
+        .subcommand(
+            Command::new("retract")
+                .about("Sign and publish a Nanopub retracting a Nanopub you published")
+                .arg(arg!(<NANOPUB_URI> "The URI of the Nanopub to retract"))
+                .arg(
+                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign. Default: NANOPUB_KEY")
+                        .default_value("")
+                )
+                .arg(
+                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
+                        .default_value("")
+                )
+                .arg(
+                    arg!(-t --test "To publish to the test server instead of the Nanopublication network.")
+                )
+                .arg(
+                    arg!(--server <URL> "URL of the server where to publish. Default: NANOPUB_SERVER, or a random server of the network")
+                        .conflicts_with("test")
+                )
                 .arg_required_else_help(true),
-        ).subcommand(
+        )
+        .subcommand(
+            Command::new("update")
+                .about("Sign and publish a new version of a Nanopub you published, superseding it")
+                .arg(arg!(<NANOPUB> "The URI or file of the Nanopub to update"))
+                .arg(
+                    arg!(-a --assertion <ASSERTION_FILE> "RDF file with the triples of the new assertion")
+                        .required(true)
+                )
+                .arg(
+                    arg!(-k --key <PRIVATE_KEY> "The path to a private key used to sign. Default: NANOPUB_KEY")
+                        .default_value("")
+                )
+                .arg(
+                    arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
+                        .default_value("")
+                )
+                .arg(
+                    arg!(-t --test "To publish to the test server instead of the Nanopublication network.")
+                )
+                .arg(
+                    arg!(--server <URL> "URL of the server where to publish. Default: NANOPUB_SERVER, or a random server of the network")
+                        .conflicts_with("test")
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("template")
+                .about("Use assertion templates to create Nanopubs")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("show")
+                        .about("Show the placeholders of a template")
+                        .arg(arg!(<TEMPLATE> "The URI or file of the template Nanopub"))
+                        .arg_required_else_help(true),
+                )
+                .subcommand(
+                    Command::new("fill")
+                        .about("Fill the placeholders of a template to create a new unsigned Nanopub")
+                        .arg(arg!(<TEMPLATE> "The URI or file of the template Nanopub"))
+                        .arg(
+                            arg!(-v --value <VALUE> "Value of a placeholder, as name=value")
+                                .action(ArgAction::Append)
+                        )
+                        .arg(
+                            arg!(-c --creator <ORCID> "The ORCID of the creator. Default: ORCID of the profile")
+                        )
+                        .arg(
+                            arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
+                                .default_value("")
+                        )
+                        .arg(
+                            arg!(-o --output <OUTPUT_FILE> "File where to write the Nanopub. Default: printed")
+                        )
+                        .arg_required_else_help(true),
+                ),
+        )
+        .subcommand(
+            Command::new("fetch")
+                .about("Fetch a Nanopub, and check it is valid")
+                .arg(arg!(<NANOPUB> "The URI or artifact code of the Nanopub to fetch"))
+                .arg(
+                    arg!(-f --format <FORMAT> "RDF format of the output: trig, nquads, or jsonld. Default: format in the config, or trig")
+                        .value_parser(|s: &str| s.parse::<RdfFormat>().map_err(|e| e.to_string()))
+                )
+                .arg(
+                    arg!(-o --output <OUTPUT> "File where to write the Nanopub, or directory when fetching recursively. Default: printed")
+                )
+                .arg(
+                    arg!(-r --recursive "Also fetch the Nanopubs referenced by the Nanopub, recursively")
+                )
+                .arg(
+                    arg!(--depth <DEPTH> "Maximum depth of references to follow when fetching recursively. Default: no limit")
+                        .value_parser(value_parser!(usize))
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Show the infos extracted from a Nanopub, and warnings on its structure")
+                .arg(arg!(<NANOPUB> "The file, URI, or artifact code of the Nanopub, or - to read from stdin"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Show the triples added and removed in each graph between two Nanopubs")
+                .arg(arg!(<OLD> "The file, URI, or artifact code of the old Nanopub, or - to read from stdin"))
+                .arg(arg!(<NEW> "The file, URI, or artifact code of the new Nanopub, or - to read from stdin"))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert a Nanopub to another RDF format, keeping its prefixes")
+                .arg(arg!(<NANOPUB_FILE> "The file to convert, or - to read from stdin"))
+                .arg(
+                    arg!(--to <FORMAT> "RDF format of the output: trig, nquads, or jsonld. Required, unless format is set in the config")
+                        .value_parser(|s: &str| s.parse::<RdfFormat>().map_err(|e| e.to_string()))
+                )
+                .arg(
+                    arg!(-o --output <OUTPUT_FILE> "File where to write the converted Nanopub, or - for stdout. Default: stdout")
+                )
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("profile")
+                .about("Setup or show your Nanopub profile")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("init")
+                        .about("Generate a key pair, and write a profile.yml using it")
+                        .arg(arg!(--orcid <ORCID> "Your ORCID, e.g. https://orcid.org/0000-0000-0000-0000"))
+                        .arg(arg!(-n --name <NAME> "Your name"))
+                        .arg(
+                            arg!(-d --dir <DIR> "Directory where to write the keys and profile.yml. Default: ~/.nanopub")
+                        )
+                        .arg(
+                            arg!(--"publish-intro" "Publish an introduction Nanopub declaring the public key for your ORCID")
+                        )
+                        .arg(
+                            arg!(-t --test "To publish the introduction to the test server instead of the Nanopublication network.")
+                        )
+                        .arg(
+                            arg!(--server <URL> "URL of the server where to publish the introduction. Default: NANOPUB_SERVER, or a random server of the network")
+                                .conflicts_with("test")
+                        )
+                        .arg(arg!(-f --force "Overwrite an existing profile")),
+                )
+                .subcommand(
+                    Command::new("show")
+                        .about("Show a profile, without the private key")
+                        .arg(
+                            arg!(-p --profile <PROFILE> "The path to a profile.yml file. Default: NANOPUB_PROFILE, or ~/.nanopub/profile.yml")
+                                .default_value("")
+                        ),
+                ),
+        )
+        .subcommand(
             Command::new("check")
                 .about("Check if a Nanopub is valid")
-                .arg(arg!(<NANOPUB_FILE> "The file to check"))
+                .arg(arg!(<NANOPUB_FILES>... "The files, directories, or globs of the files to check, or - to read from stdin"))
                 .arg_required_else_help(true),
         )
         .subcommand(
@@ -57,43 +305,82 @@ async fn main() -> Result<(), Box<dyn Error>> {
         );
 
     let matches = cmd.clone().get_matches();
+    let json = matches.get_flag("json");
+    let command = matches.subcommand_name().unwrap_or_default().to_string();
+    match run(&mut cmd, &matches, json).await {
+        Ok(report) => {
+            if let Some(report) = report.filter(|_| json) {
+                report.print();
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => e.exit(&command, json),
+    }
+}
+
+/// Run the subcommand. Returns the report to print with `--json`
+async fn run(
+    cmd: &mut Command,
+    matches: &ArgMatches,
+    json: bool,
+) -> Result<Option<Report>, CliError> {
+    let store = matches
+        .get_one::<String>("cache-dir")
+        .map(NanopubStore::new)
+        .transpose()?;
 
+    let mut report = None;
     match matches.subcommand() {
         Some(("sign", sub)) => {
-            let np_file = sub.get_one::<String>("NANOPUB_FILE").expect("required");
+            let np_files: Vec<&String> = sub
+                .get_many::<String>("NANOPUB_FILES")
+                .expect("required")
+                .collect();
             let key_file = sub.get_one::<String>("key").expect("has default value");
             let profile_file = sub.get_one::<String>("profile").expect("has default value");
+            let output = sub.get_one::<String>("output");
+            let in_place = sub.get_flag("in-place");
 
-            // Read RDF from file, and get profile from YAML file or key
-            let np_rdf = fs::read_to_string(np_file)?;
-            let profile = if !key_file.is_empty() {
-                let privkey = fs::read_to_string(key_file)?;
-                ProfileBuilder::new(privkey).build()?
+            let single = single_file(&np_files);
+            if single.is_none() && output.is_some() {
+                return Err(
+                    NpError("--output can only be used to sign a single file".to_string()).into(),
+                );
+            }
+
+            // Get profile from YAML file or key
+            let profile = load_profile(key_file, profile_file).or_fail(Failure::Profile)?;
+            if let Some(np_file) = single {
+                // By default, prefix the nanopub filename with "signed."
+                let output = output_path(np_file, output, in_place, "signed")?;
+                // Messages would mix with the nanopub printed to stdout
+                let quiet = json || output == STDIO;
+                report = Some(sign_file(np_file, &output, &profile, quiet, json)?);
             } else {
-                ProfileBuilder::from_file(profile_file)?
-            };
-            println!("✍️  Signing {}", np_file);
-            let np = Nanopub::new(&np_rdf)?.sign(&profile)?;
-            println!("{}", np);
-
-            // Prefix the nanopub filename with "signed."
-            let path = Path::new(np_file);
-            let parent = path.parent().unwrap_or_else(|| Path::new(""));
-            let file_name_without_extension = path
-                .file_stem()
-                .ok_or_else(|| NpError(format!("Error getting filename from {:?}", path)))?
-                .to_str()
-                .ok_or_else(|| NpError(format!("Error getting filename from {:?}", path)))?;
-            let new_file_name = format!("signed.{}.trig", file_name_without_extension);
-            let signed_path = parent.join(new_file_name);
-            println!(
-                "📁 Signed Nanopub stored to {}",
-                signed_path.to_str().ok_or_else(|| NpError(format!(
-                    "Error getting signed path {:?}",
-                    signed_path
-                )))?
-            );
-            let _ = fs::write(signed_path, np.rdf()?);
+                let mut batch = expand_paths(&np_files).or_fail(Failure::Io)?;
+                if !in_place {
+                    // The signed files are written next to the inputs, they are not signed again on the next run
+                    let total = batch.files.len();
+                    batch.files.retain(|file| {
+                        !file
+                            .file_name()
+                            .is_some_and(|name| name.to_string_lossy().starts_with("signed."))
+                    });
+                    if batch.files.len() < total {
+                        status!(
+                            json,
+                            "⏭️  Skipping {} files already signed",
+                            total - batch.files.len()
+                        );
+                    }
+                }
+                status!(json, "✍️  Signing {} files", batch.files.len());
+                let results = process_files(&batch.files, |np_file| {
+                    let output = output_path(np_file, None, in_place, "signed")?;
+                    sign_file(np_file, &output, &profile, true, json)
+                });
+                report = Some(summarize("sign", batch, results, json)?);
+            }
         }
         Some(("publish", sub)) => {
             let np_file = sub.get_one::<String>("NANOPUB_FILE").expect("required");
@@ -102,42 +389,852 @@ async fn main() -> Result<(), Box<dyn Error>> {
             let test_server = sub.get_flag("test");
 
             // Read RDF from file, and get profile from YAML file or key
-            let np_rdf = fs::read_to_string(np_file)?;
-            let profile = if !key_file.is_empty() {
-                let privkey = fs::read_to_string(key_file)?;
-                ProfileBuilder::new(privkey).build()?
+            let np_rdf = read_input(np_file).or_fail(Failure::Io)?;
+            let profile = load_profile(key_file, profile_file).or_fail(Failure::Profile)?;
+            // Sign before publishing, to tell invalid nanopubs apart from network failures
+            let np = Nanopub::new(&np_rdf)
+                .and_then(|np| np.sign(&profile))
+                .or_fail(Failure::InvalidNanopub)?;
+            let np = publish_to_network(
+                np,
+                None,
+                test_server,
+                server(sub)?.as_deref(),
+                np_file,
+                json,
+            )
+            .await
+            .or_fail(Failure::Network)?;
+            status!(json, "✅ Published {}", np.info.uri.as_str());
+            cache_published(store.as_ref(), &np);
+            report = Some(Report::new("publish", &np));
+        }
+        Some(("unsign", sub)) => {
+            let np_file = sub.get_one::<String>("NANOPUB_FILE").expect("required");
+            let output = output_path(
+                np_file,
+                sub.get_one::<String>("output"),
+                sub.get_flag("in-place"),
+                "unsigned",
+            )?;
+            let quiet = json || output == STDIO;
+            status!(quiet, "🔓 Unsigning {np_file}");
+            let np = Nanopub::new(&read_input(np_file).or_fail(Failure::Io)?)
+                .and_then(|np| np.unsign())
+                .or_fail(Failure::InvalidNanopub)?;
+            let mut np_report = Report::new("unsign", &np);
+            if output == STDIO && json {
+                np_report.rdf = Some(np.rdf()?);
             } else {
-                ProfileBuilder::from_file(profile_file)?
+                write_output(&output, &np.rdf_as(file_format(&output))?).or_fail(Failure::Io)?;
+                status!(quiet, "📁 Unsigned Nanopub stored to {output}");
+                np_report.file = Some(output).filter(|output| output != STDIO);
+            }
+            report = Some(np_report);
+        }
+        Some(("intro", sub)) => {
+            let key_file = sub.get_one::<String>("key").expect("has default value");
+            let profile_file = sub.get_one::<String>("profile").expect("has default value");
+            let test_server = sub.get_flag("test");
+
+            let profile = load_profile(key_file, profile_file).or_fail(Failure::Profile)?;
+            let np = Nanopub::new_intro(&profile).or_fail(Failure::InvalidNanopub)?;
+            if let Some(output) = sub.get_one::<String>("output") {
+                let np = np.sign(&profile).or_fail(Failure::InvalidNanopub)?;
+                fs::write(output, np.rdf()?).or_fail(Failure::Io)?;
+                status!(json, "📁 Signed introduction stored to {output}");
+                report = Some(Report {
+                    file: Some(output.clone()),
+                    ..Report::new("intro", &np)
+                });
+            } else {
+                let np = publish_to_network(
+                    np,
+                    Some(&profile),
+                    test_server,
+                    server(sub)?.as_deref(),
+                    "introduction",
+                    json,
+                )
+                .await
+                .or_fail(Failure::Network)?;
+                status!(json, "✅ Published {}", np.info.uri.as_str());
+                status!(
+                    json,
+                    "💡 Add it to your profile.yml: introduction_nanopub_uri: {}",
+                    np.info.uri.as_str()
+                );
+                cache_published(store.as_ref(), &np);
+                report = Some(Report::new("intro", &np));
+            }
+        }
+        Some(("retract", sub)) => {
+            let target_uri = sub.get_one::<String>("NANOPUB_URI").expect("required");
+            let key_file = sub.get_one::<String>("key").expect("has default value");
+            let profile_file = sub.get_one::<String>("profile").expect("has default value");
+            let test_server = sub.get_flag("test");
+
+            let profile = load_profile(key_file, profile_file).or_fail(Failure::Profile)?;
+            status!(json, "🗑️  Retracting {target_uri}");
+            let np = Nanopub::new_retraction(target_uri, &profile)
+                .and_then(|np| np.sign(&profile))
+                .or_fail(Failure::InvalidNanopub)?;
+            // Make sure the retraction is signed with the same key as the target, when it can be fetched
+            let target = match &store {
+                Some(store) => store.fetch(target_uri).await,
+                None => Nanopub::fetch(target_uri).await,
             };
-            if test_server {
-                println!("🧪 Publishing {np_file} to test server");
-                let np = Nanopub::new(&np_rdf)?.publish(Some(&profile), None).await?;
-                println!("✅ Published {}", np.info.uri);
+            match target {
+                Ok(target) => np.check_retraction(&target)?,
+                Err(e) => eprintln!(
+                    "⚠️  Could not fetch {target_uri} to check it is signed with the same key: {e}"
+                ),
+            }
+            let np = publish_to_network(
+                np,
+                None,
+                test_server,
+                server(sub)?.as_deref(),
+                "retraction",
+                json,
+            )
+            .await
+            .or_fail(Failure::Network)?;
+            status!(json, "✅ Published {}", np.info.uri.as_str());
+            cache_published(store.as_ref(), &np);
+            report = Some(Report::new("retract", &np));
+        }
+        Some(("update", sub)) => {
+            let np_ref = sub.get_one::<String>("NANOPUB").expect("required");
+            let assertion_file = sub.get_one::<String>("assertion").expect("required");
+            let key_file = sub.get_one::<String>("key").expect("has default value");
+            let profile_file = sub.get_one::<String>("profile").expect("has default value");
+            let test_server = sub.get_flag("test");
+
+            let profile = load_profile(key_file, profile_file).or_fail(Failure::Profile)?;
+            let old_np = load_nanopub(np_ref, store.as_ref()).await?;
+            let (assertion_dataset, _prefixes) =
+                parse_rdf(&read_input(assertion_file).or_fail(Failure::Io)?)
+                    .or_fail(Failure::InvalidNanopub)?;
+            status!(json, "📝 Updating {}", old_np.info.uri.as_str());
+            let np = old_np
+                .supersede(&profile, |assertion| {
+                    assertion.clear();
+                    for quad in assertion_dataset.iter() {
+                        assertion.insert(quad);
+                    }
+                })
+                .or_fail(Failure::InvalidNanopub)?;
+            let np = publish_to_network(
+                np,
+                None,
+                test_server,
+                server(sub)?.as_deref(),
+                "new version",
+                json,
+            )
+            .await
+            .or_fail(Failure::Network)?;
+            status!(json, "✅ Published {}", np.info.uri.as_str());
+            cache_published(store.as_ref(), &np);
+            report = Some(Report::new("update", &np));
+        }
+        Some(("template", sub)) => match sub.subcommand() {
+            Some(("show", sub)) => {
+                let template_ref = sub.get_one::<String>("TEMPLATE").expect("required");
+                let template = Template::new(load_nanopub(template_ref, store.as_ref()).await?)
+                    .or_fail(Failure::InvalidNanopub)?;
+                if json {
+                    return Ok(Some(Report {
+                        placeholders: template.placeholders,
+                        ..Report::new("template", &template.np)
+                    }));
+                }
+                println!(
+                    "📋 {BOLD}{}{END} {}",
+                    template.label.as_deref().unwrap_or(""),
+                    template.np.info.uri.as_str()
+                );
+                for placeholder in &template.placeholders {
+                    let optional = template.is_optional(placeholder);
+                    let kind = if placeholder.is_literal() {
+                        "literal"
+                    } else if placeholder.is_local() {
+                        "local name"
+                    } else {
+                        "IRI"
+                    };
+                    println!(
+                        "  {BOLD}{}{END} ({kind}{}): {}",
+                        placeholder.name,
+                        if optional { ", optional" } else { "" },
+                        placeholder.label.as_deref().unwrap_or("")
+                    );
+                    if let Some(prefix) = &placeholder.prefix {
+                        println!("      prefix: {prefix}");
+                    }
+                    if let Some(regex) = &placeholder.regex {
+                        println!("      regex: {regex}");
+                    }
+                    for value in &placeholder.possible_values {
+                        println!("      possible value: {value}");
+                    }
+                }
+            }
+            Some(("fill", sub)) => {
+                let template_ref = sub.get_one::<String>("TEMPLATE").expect("required");
+                let profile_file = sub.get_one::<String>("profile").expect("has default value");
+                let mut values = HashMap::new();
+                for value in sub.get_many::<String>("value").unwrap_or_default() {
+                    let (name, value) = value.split_once('=').ok_or_else(|| {
+                        NpError(format!("Invalid value {value}, expected name=value"))
+                    })?;
+                    values.insert(name.to_string(), value.to_string());
+                }
+                let creator = match sub.get_one::<String>("creator") {
+                    Some(creator) => creator.clone(),
+                    None => ProfileBuilder::from_file(&profile_path(profile_file)?)?
+                        .orcid_id
+                        .ok_or_else(|| {
+                            NpError(
+                                "No ORCID in the profile, provide the creator with --creator"
+                                    .to_string(),
+                            )
+                        })?,
+                };
+                let template = Template::new(load_nanopub(template_ref, store.as_ref()).await?)
+                    .or_fail(Failure::InvalidNanopub)?;
+                let np = template.fill(&values, &creator)?;
+                let mut np_report = Report::new("template", &np);
+                match sub.get_one::<String>("output") {
+                    Some(output) => {
+                        fs::write(output, np.rdf()?).or_fail(Failure::Io)?;
+                        status!(json, "📁 Nanopub stored to {output}");
+                        np_report.file = Some(output.clone());
+                    }
+                    None if json => np_report.rdf = Some(np.rdf()?),
+                    None => println!("{}", np.rdf()?),
+                }
+                report = Some(np_report);
+            }
+            _ => {}
+        },
+        Some(("fetch", sub)) => {
+            let np_ref = sub.get_one::<String>("NANOPUB").expect("required");
+            let format =
+                output_format(sub.get_one::<RdfFormat>("format"))?.unwrap_or(RdfFormat::TriG);
+            let output = sub.get_one::<String>("output");
+            let recursive = sub.get_flag("recursive");
+            let max_depth = sub.get_one::<usize>("depth").copied();
+
+            // Fetch breadth-first, the referenced nanopubs are only fetched once
+            let mut queue = VecDeque::from([(np_ref.clone(), 0)]);
+            let mut seen =
+                HashSet::from([extract_artifact_code(np_ref).unwrap_or(np_ref).to_string()]);
+            let mut reports: Vec<Report> = Vec::new();
+            while let Some((uri, depth)) = queue.pop_front() {
+                eprintln!("📡 Fetching {uri}");
+                let fetched = match &store {
+                    Some(store) => store.fetch(&uri).await,
+                    None => Nanopub::fetch(&uri).await,
+                };
+                let checked = fetched.or_fail(Failure::Network).and_then(|np| {
+                    let result = np.verify().or_fail(Failure::InvalidNanopub)?;
+                    let np_report = Report {
+                        check: Some(result.clone()),
+                        ..Report::new("fetch", &np)
+                    };
+                    match result.error {
+                        Some(error) => Err(CliError {
+                            failure: Failure::CheckFailed,
+                            message: error,
+                            report: Some(Box::new(np_report)),
+                        }),
+                        None => Ok((np, np_report)),
+                    }
+                });
+                let (np, mut np_report) = match checked {
+                    Ok(checked) => checked,
+                    // A referenced nanopub that cannot be fetched does not stop the others
+                    Err(e) if depth > 0 => {
+                        eprintln!("⚠️  Could not fetch {uri}: {}", e.message);
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+                eprintln!("✅ {BOLD}{}{END} is valid", np.info.uri.as_str());
+                if recursive && max_depth.is_none_or(|max| depth < max) {
+                    for reference in np.references() {
+                        let key = extract_artifact_code(&reference)
+                            .unwrap_or(&reference)
+                            .to_string();
+                        if seen.insert(key) {
+                            queue.push_back((reference, depth + 1));
+                        }
+                    }
+                }
+
+                let rdf = np.rdf_as(format)?;
+                match output {
+                    Some(output) if recursive => {
+                        // Nanopubs without trusty hash are named after their URI, so they do not overwrite each other
+                        let name = if np.info.trusty_hash.is_empty() {
+                            file_name_for_uri(np.info.uri.as_str())
+                        } else {
+                            np.info.trusty_hash.clone()
+                        };
+                        fs::create_dir_all(output).or_fail(Failure::Io)?;
+                        let path = Path::new(output).join(format!("{name}.{}", extension(format)));
+                        fs::write(&path, rdf).or_fail(Failure::Io)?;
+                        np_report.file = Some(path.display().to_string());
+                    }
+                    Some(output) => {
+                        fs::write(output, rdf).or_fail(Failure::Io)?;
+                        np_report.file = Some(output.clone());
+                    }
+                    None if json => np_report.rdf = Some(rdf),
+                    None => println!("{rdf}"),
+                }
+                reports.push(np_report);
+            }
+            if let Some(output) = output {
+                eprintln!("📁 {} Nanopubs stored to {output}", reports.len());
+            }
+            // The fetched nanopub comes first, the others are the referenced nanopubs
+            let mut np_report = reports.remove(0);
+            np_report.references = reports;
+            report = Some(np_report);
+        }
+        Some(("info", sub)) => {
+            let np_ref = sub.get_one::<String>("NANOPUB").expect("required");
+            let np = inspect_nanopub(np_ref, store.as_ref()).await?;
+            let graphs: Vec<GraphReport> = [
+                NpPart::Head,
+                NpPart::Assertion,
+                NpPart::Provenance,
+                NpPart::PubInfo,
+            ]
+            .into_iter()
+            .map(|part| GraphReport {
+                graph: graph_name(part).to_string(),
+                iri: np.graph_iri(part).as_str().to_string(),
+                triples: np.graph(part).len(),
+            })
+            .collect();
+            let mut warnings = np.warnings();
+            // An invalid hash or signature is shown, without failing like `np check`
+            let check = np.verify().ok();
+            if let Some(error) = check.as_ref().and_then(|check| check.error.clone()) {
+                warnings.push(error);
+            }
+            if !json {
+                print_info(&np, &graphs, &warnings);
+            }
+            report = Some(Report {
+                check,
+                graphs,
+                warnings: Some(warnings),
+                ..Report::new("info", &np)
+            });
+        }
+        Some(("diff", sub)) => {
+            let old_ref = sub.get_one::<String>("OLD").expect("required");
+            let new_ref = sub.get_one::<String>("NEW").expect("required");
+            let old = load_nanopub(old_ref, store.as_ref()).await?;
+            let new = load_nanopub(new_ref, store.as_ref()).await?;
+            let diff = NpDiff::new(&old, &new);
+            status!(
+                json,
+                "🔀 Changes from {BOLD}{}{END} to {BOLD}{}{END}\n",
+                old.info.uri.as_str(),
+                new.info.uri.as_str()
+            );
+            if diff.is_empty() {
+                status!(json, "✅ No changes");
             } else {
-                let server = get_np_server(true);
-                println!("📬️ Publishing {np_file} to {server}");
-                let np = Nanopub::new(&np_rdf)?
-                    .publish(Some(&profile), Some(server))
-                    .await?;
-                println!("✅ Published {}", np.info.uri);
+                status!(json, "{diff}");
             }
+            let changes = diff
+                .graphs
+                .iter()
+                .filter(|graph| !graph.is_empty())
+                .map(|graph| GraphChanges {
+                    graph: graph_name(graph.part).to_string(),
+                    added: graph.added.iter().map(|t| t.to_string()).collect(),
+                    removed: graph.removed.iter().map(|t| t.to_string()).collect(),
+                })
+                .collect();
+            report = Some(Report {
+                changes: Some(changes),
+                ..Report::new("diff", &new)
+            });
         }
-        Some(("check", sub)) => {
+        Some(("convert", sub)) => {
             let np_file = sub.get_one::<String>("NANOPUB_FILE").expect("required");
-            // Read RDF file
-            let np_rdf = fs::read_to_string(np_file)?;
-            println!("🔎 Checking {}", np_file);
-            Nanopub::new(&np_rdf)?.check()?;
-            // println!("{}", np);
+            let Some(format) = output_format(sub.get_one::<RdfFormat>("to"))? else {
+                cmd.find_subcommand_mut("convert")
+                    .expect("defined")
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "--to <FORMAT> is required, unless format is set in the config",
+                    )
+                    .exit();
+            };
+            let output = sub
+                .get_one::<String>("output")
+                .map_or(STDIO, |output| output.as_str());
+
+            // Parse the nanopub, its prefixes are used when serializing to TriG or JSON-LD
+            let np_rdf = read_input(np_file).or_fail(Failure::Io)?;
+            let np = Nanopub::new(&np_rdf).or_fail(Failure::InvalidNanopub)?;
+            let rdf = np.rdf_as(format)?;
+
+            let mut np_report = Report::new("convert", &np);
+            if output == STDIO && json {
+                np_report.rdf = Some(rdf);
+            } else {
+                write_output(output, &rdf).or_fail(Failure::Io)?;
+                status!(
+                    json || output == STDIO,
+                    "🔄 Nanopub converted to {format} and stored to {output}"
+                );
+                np_report.file = Some(output.to_string()).filter(|output| output != STDIO);
+            }
+            report = Some(np_report);
+        }
+        Some(("profile", sub)) => match sub.subcommand() {
+            Some(("init", sub)) => {
+                let dir = sub
+                    .get_one::<String>("dir")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(default_profile_dir);
+                let publish_intro = sub.get_flag("publish-intro");
+                if dir.join("profile.yml").exists() && !sub.get_flag("force") {
+                    return Err(NpError(format!(
+                        "A profile already exists in {}, use --force to overwrite it",
+                        dir.display()
+                    ))
+                    .into());
+                }
+                if publish_intro && !(sub.contains_id("orcid") && sub.contains_id("name")) {
+                    return Err(NpError(
+                        "An ORCID and a name are required to publish an introduction".to_string(),
+                    )
+                    .into());
+                }
+                status!(json, "🔑 Generating a new key pair");
+                let (privkey, pubkey) = gen_keys()?;
+                let mut profile = ProfileBuilder::new(privkey).with_public_key(pubkey);
+                if let Some(orcid) = sub.get_one::<String>("orcid") {
+                    profile = profile.with_orcid(orcid.clone());
+                }
+                if let Some(name) = sub.get_one::<String>("name") {
+                    profile = profile.with_name(name.clone());
+                }
+                let mut profile = profile.build()?;
+                // Save the keys before publishing, so they are not lost if publishing fails
+                let profile_path = profile.save(&dir).or_fail(Failure::Io)?;
+                status!(json, "📁 Profile stored to {}", profile_path.display());
+                let mut profile_report = Report::default();
+                if publish_intro {
+                    let np = publish_to_network(
+                        Nanopub::new_intro(&profile)?,
+                        Some(&profile),
+                        sub.get_flag("test"),
+                        server(sub)?.as_deref(),
+                        "introduction",
+                        json,
+                    )
+                    .await
+                    .or_fail(Failure::Network)?;
+                    status!(json, "✅ Published {}", np.info.uri.as_str());
+                    profile.introduction_nanopub_uri = Some(np.info.uri.as_str().to_string());
+                    profile.save(&dir).or_fail(Failure::Io)?;
+                    profile_report = Report::new("profile", &np);
+                }
+                report = Some(Report {
+                    command: "profile".to_string(),
+                    profile: Some(ProfileReport::from(&profile)),
+                    file: Some(profile_path.display().to_string()),
+                    ..profile_report
+                });
+            }
+            Some(("show", sub)) => {
+                let profile_file = sub.get_one::<String>("profile").expect("has default value");
+                let profile_file = profile_path(profile_file)?;
+                let profile = ProfileBuilder::from_file(&profile_file).or_fail(Failure::Profile)?;
+                if json {
+                    return Ok(Some(Report {
+                        command: "profile".to_string(),
+                        profile: Some(ProfileReport::from(&profile)),
+                        file: Some(profile_file).filter(|file| !file.is_empty()),
+                        ..Default::default()
+                    }));
+                }
+                println!("👤 {BOLD}Nanopub profile{END}");
+                println!(
+                    "  ORCID: {}",
+                    profile.orcid_id.as_deref().unwrap_or("not defined")
+                );
+                println!(
+                    "  Name: {}",
+                    profile.name.as_deref().unwrap_or("not defined")
+                );
+                println!(
+                    "  Introduction: {}",
+                    profile
+                        .introduction_nanopub_uri
+                        .as_deref()
+                        .unwrap_or("not published")
+                );
+                println!("  Public key: {}", profile.public_key);
+            }
+            _ => {}
+        },
+        Some(("check", sub)) => {
+            let np_files: Vec<&String> = sub
+                .get_many::<String>("NANOPUB_FILES")
+                .expect("required")
+                .collect();
+            if let Some(np_file) = single_file(&np_files) {
+                report = Some(check_file(np_file, json)?);
+            } else {
+                let batch = expand_paths(&np_files).or_fail(Failure::Io)?;
+                status!(json, "🔎 Checking {} files", batch.files.len());
+                let results = process_files(&batch.files, |np_file| check_file(np_file, true));
+                report = Some(summarize("check", batch, results, json)?);
+            }
         }
         Some(("completions", sub)) => {
             let shell = sub.get_one::<Shell>("SHELL").expect("required");
             eprintln!("Generating completion file for {shell}...");
-            print_completions(shell.to_owned(), &mut cmd);
+            print_completions(shell.to_owned(), cmd);
         }
         _ => {}
     }
-    Ok(())
+    Ok(report)
+}
+
+/// Sign a nanopub file, and write the signed nanopub to the output
+fn sign_file(
+    np_file: &str,
+    output: &str,
+    profile: &NpProfile,
+    quiet: bool,
+    json: bool,
+) -> Result<Report, CliError> {
+    // Read RDF from file
+    let np_rdf = read_input(np_file).or_fail(Failure::Io)?;
+    status!(quiet, "✍️  Signing {}", np_file);
+    let np = Nanopub::new(&np_rdf)
+        .and_then(|np| np.sign(profile))
+        .or_fail(Failure::InvalidNanopub)?;
+    status!(quiet, "{}", np);
+
+    let mut np_report = Report::new("sign", &np);
+    if output == STDIO && json {
+        np_report.rdf = Some(np.rdf()?);
+    } else {
+        write_output(output, &np.rdf_as(file_format(output))?).or_fail(Failure::Io)?;
+        status!(quiet, "📁 Signed Nanopub stored to {output}");
+        np_report.file = Some(output.to_string()).filter(|output| output != STDIO);
+    }
+    Ok(np_report)
+}
+
+/// Check the trusty hash and signature of a nanopub file
+fn check_file(np_file: &str, quiet: bool) -> Result<Report, CliError> {
+    // Read RDF file
+    let np_rdf = read_input(np_file).or_fail(Failure::Io)?;
+    status!(quiet, "🔎 Checking {}", np_file);
+    let np = Nanopub::new(&np_rdf).or_fail(Failure::InvalidNanopub)?;
+    let result = np.verify().or_fail(Failure::InvalidNanopub)?;
+    let np_report = Report {
+        check: Some(result.clone()),
+        ..Report::new("check", &np)
+    };
+    if let Some(error) = result.error {
+        return Err(CliError {
+            failure: Failure::CheckFailed,
+            message: error,
+            report: Some(Box::new(np_report)),
+        });
+    }
+    status!(
+        quiet,
+        "\n✅ Nanopub {BOLD}{}{END} is valid: {result}",
+        result.uri
+    );
+    Ok(np_report)
+}
+
+/// The file to process when a single file (or stdin) is given, `None` for several files, directories, or globs
+fn single_file<'a>(paths: &[&'a String]) -> Option<&'a String> {
+    match paths {
+        [path] if !is_glob(path) && !Path::new(path.as_str()).is_dir() => Some(path),
+        _ => None,
+    }
+}
+
+/// Read the content of a file, or of stdin for `-`
+fn read_input(path: &str) -> Result<String, NpError> {
+    if path == STDIO {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        fs::read_to_string(path).map_err(|e| NpError(format!("Error reading {path}: {e}")))
+    }
+}
+
+/// Write the content to a file, or to stdout for `-`
+fn write_output(path: &str, content: &str) -> Result<(), NpError> {
+    if path == STDIO {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(content.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    } else {
+        fs::write(path, content).map_err(|e| NpError(format!("Error writing {path}: {e}")))
+    }
+}
+
+/// Where to write the nanopub created from the input file: the given output, the input itself when in place,
+/// stdout when reading from stdin, or by default a file next to the input with a prefixed name
+fn output_path(
+    np_file: &str,
+    output: Option<&String>,
+    in_place: bool,
+    prefix: &str,
+) -> Result<String, NpError> {
+    if in_place {
+        if np_file == STDIO {
+            return Err(NpError(
+                "Cannot write in place when reading from stdin".to_string(),
+            ));
+        }
+        return Ok(np_file.to_string());
+    }
+    match output {
+        Some(output) => Ok(output.clone()),
+        None if np_file == STDIO => Ok(STDIO.to_string()),
+        None => Ok(prefixed_path(np_file, prefix)?.display().to_string()),
+    }
+}
+
+/// Path of a file next to the given nanopub file, with its name prefixed and its extension kept, e.g. `signed.nanopub.trig`
+fn prefixed_path(np_file: &str, prefix: &str) -> Result<PathBuf, NpError> {
+    let path = Path::new(np_file);
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| NpError(format!("Error getting filename from {:?}", path)))?
+        .to_str()
+        .ok_or_else(|| NpError(format!("Error getting filename from {:?}", path)))?;
+    Ok(parent.join(format!("{prefix}.{file_name}")))
+}
+
+/// Get the profile from a private key file if provided, or from a profile.yml file.
+///
+/// The key or profile given on the command line take precedence over the config, which is only loaded when none is given
+fn load_profile(key_file: &str, profile_file: &str) -> Result<NpProfile, NpError> {
+    let key_file = match (key_file, profile_file) {
+        ("", "") => Config::load()?.key.unwrap_or_default(),
+        _ => key_file.to_string(),
+    };
+    if !key_file.is_empty() {
+        let privkey = fs::read_to_string(key_file)?;
+        ProfileBuilder::new(privkey).build()
+    } else {
+        ProfileBuilder::from_file(&profile_path(profile_file)?)
+    }
+}
+
+/// The profile file given on the command line, or the one from the config
+fn profile_path(profile_file: &str) -> Result<String, NpError> {
+    match profile_file {
+        "" => Ok(Config::load()?.profile.unwrap_or_default()),
+        profile_file => Ok(profile_file.to_string()),
+    }
+}
+
+/// The server given on the command line, or the one from the config when not publishing to the test server
+fn server(sub: &ArgMatches) -> Result<Option<String>, NpError> {
+    match sub.get_one::<String>("server") {
+        Some(server) => Ok(Some(server.clone())),
+        None if sub.get_flag("test") => Ok(None),
+        None => Ok(Config::load()?.server),
+    }
+}
+
+/// The RDF format given on the command line, or the one from the config
+fn output_format(format: Option<&RdfFormat>) -> Result<Option<RdfFormat>, NpError> {
+    match format {
+        Some(format) => Ok(Some(*format)),
+        None => Ok(Config::load()?.format),
+    }
+}
+
+/// Print the infos of a nanopub, the number of triples in its graphs, and the warnings on its structure
+fn print_info(np: &Nanopub, graphs: &[GraphReport], warnings: &[String]) {
+    let info = &np.info;
+    let or_none = |value: &str| {
+        if value.is_empty() {
+            "none".to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    println!("🔗 Nanopub {BOLD}{}{END}", info.uri.as_str());
+    println!("  Namespace         {}", info.ns.as_str());
+    println!("  Base URI          {}", info.base_uri);
+    println!(
+        "  Separators        {:?} before, {:?} after the trusty hash",
+        info.separator_before_trusty, info.separator_after_trusty
+    );
+    println!("  Trusty hash       {}", or_none(&info.trusty_hash));
+    println!("  Algorithm         {}", or_none(&info.algo));
+    println!("  ORCID             {}", or_none(&info.orcid));
+    if !info.creators.is_empty() {
+        println!("  Creators          {}", info.creators.join(", "));
+    }
+    if let Some(created) = &info.created {
+        println!("  Created           {}", created.to_rfc3339());
+    }
+    if !info.types.is_empty() {
+        println!("  Types             {}", info.types.join(", "));
+    }
+    if let Some(published) = &info.published {
+        println!("  Published         {published}");
+    }
+    println!("\n📊 Graphs");
+    println!("  {:<12} {:>7}  IRI", "Graph", "Triples");
+    for graph in graphs {
+        println!("  {:<12} {:>7}  {}", graph.graph, graph.triples, graph.iri);
+    }
+    if !warnings.is_empty() {
+        println!("\n⚠️  Warnings");
+        for warning in warnings {
+            println!("  - {warning}");
+        }
+    }
+}
+
+/// Keep a copy of a published nanopub in the cache, without failing once it is published
+fn cache_published(store: Option<&NanopubStore>, np: &Nanopub) {
+    if let Some(store) = store {
+        if let Err(e) = store.put(np) {
+            eprintln!(
+                "⚠️  Could not store {} in the cache: {e}",
+                np.info.uri.as_str()
+            );
+        }
+    }
+}
+
+/// Get a nanopub from a file, or fetch it from its URI
+async fn load_nanopub(np_ref: &str, store: Option<&NanopubStore>) -> Result<Nanopub, CliError> {
+    if np_ref == STDIO || Path::new(np_ref).is_file() {
+        let np_rdf = read_input(np_ref).or_fail(Failure::Io)?;
+        Nanopub::new(&np_rdf).or_fail(Failure::InvalidNanopub)
+    } else {
+        match store {
+            Some(store) => store.fetch(np_ref).await,
+            None => Nanopub::fetch(np_ref).await,
+        }
+        .or_fail(Failure::Network)
+    }
+}
+
+/// Load a nanopub to inspect it, from a file, the store, or the network without checking its hash and signature
+async fn inspect_nanopub(np_ref: &str, store: Option<&NanopubStore>) -> Result<Nanopub, CliError> {
+    if np_ref == STDIO || Path::new(np_ref).is_file() {
+        return load_nanopub(np_ref, store).await;
+    }
+    if let Some(np) = store
+        .map(|store| store.get(np_ref))
+        .transpose()
+        .or_fail(Failure::Io)?
+        .flatten()
+    {
+        return Ok(np);
+    }
+    Nanopub::fetch_unchecked(np_ref)
+        .await
+        .or_fail(Failure::Network)
+}
+
+/// Name of a graph of a nanopub in the reports
+fn graph_name(part: NpPart) -> &'static str {
+    match part {
+        NpPart::Head => "head",
+        NpPart::Assertion => "assertion",
+        NpPart::Provenance => "provenance",
+        NpPart::PubInfo => "pubinfo",
+    }
+}
+
+/// Publish a nanopub to the test server, to the given server, or to a random server of the Nanopublication network.
+///
+/// The nanopub is signed if a profile is provided, otherwise it needs to be already signed.
+async fn publish_to_network(
+    np: Nanopub,
+    profile: Option<&NpProfile>,
+    test_server: bool,
+    server: Option<&str>,
+    label: &str,
+    json: bool,
+) -> Result<Nanopub, NpError> {
+    if test_server {
+        status!(json, "🧪 Publishing {label} to test server");
+        np.publish(profile, None).await
+    } else {
+        let server = match server {
+            Some(server) => server.to_string(),
+            None => {
+                // Pick a server among the healthiest ones reported by the monitor
+                if let Err(e) = init_np_servers().await {
+                    eprintln!("⚠️  Could not load the servers from the monitor: {e}");
+                }
+                get_np_server(true)
+            }
+        };
+        status!(json, "📬️ Publishing {label} to {server}");
+        np.publish(profile, Some(&server)).await
+    }
+}
+
+/// Name of a file for a URI, without its scheme, keeping only letters, digits, `-`, and `_`
+fn file_name_for_uri(uri: &str) -> String {
+    let uri = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    uri.trim_end_matches(['/', '#', '.'])
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// RDF format of a nanopub file from its extension, TriG by default
+fn file_format(path: &str) -> RdfFormat {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("nq") => RdfFormat::NQuads,
+        Some("jsonld") => RdfFormat::JsonLd,
+        _ => RdfFormat::TriG,
+    }
+}
+
+/// File extension for the given RDF format
+fn extension(format: RdfFormat) -> &'static str {
+    match format {
+        RdfFormat::TriG => "trig",
+        RdfFormat::NQuads => "nq",
+        RdfFormat::JsonLd => "jsonld",
+    }
 }
 
 fn print_completions<G: Generator>(gen: G, cmd: &mut Command) {
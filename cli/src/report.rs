@@ -0,0 +1,175 @@
+use nanopub::{extract::NpInfo, template::Placeholder, CheckResult, Nanopub, NpProfile};
+use serde::Serialize;
+use std::{error::Error, fmt, process::ExitCode};
+
+/// Class of failure of a command, each one exits with a distinct code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// Any other error
+    Error = 1,
+    /// The RDF could not be parsed, or is not a valid nanopub
+    InvalidNanopub = 3,
+    /// The trusty hash or the signature of the nanopub does not match
+    CheckFailed = 4,
+    /// Fetching or publishing the nanopub failed
+    Network = 5,
+    /// The profile or the private key could not be loaded
+    Profile = 6,
+    /// A file could not be read or written
+    Io = 7,
+}
+
+/// Error of a command, with its class of failure
+#[derive(Debug)]
+pub struct CliError {
+    pub failure: Failure,
+    pub message: String,
+    /// Report on the nanopub that failed, e.g. when its check failed
+    pub report: Option<Box<Report>>,
+}
+
+impl<E: Error> From<E> for CliError {
+    fn from(err: E) -> Self {
+        Self {
+            failure: Failure::Error,
+            message: err.to_string(),
+            report: None,
+        }
+    }
+}
+
+impl CliError {
+    /// Print the error, as JSON or for humans, and get the exit code of its class of failure
+    pub fn exit(self, command: &str, json: bool) -> ExitCode {
+        let code = self.failure as u8;
+        if json {
+            let mut report = self.report.map(|r| *r).unwrap_or_default();
+            report.command = command.to_string();
+            report.error = Some(self.message);
+            report.exit_code = code;
+            report.print();
+        } else {
+            eprintln!("❌ {}", self.message);
+        }
+        ExitCode::from(code)
+    }
+}
+
+/// Attach a class of failure to the error of a result
+pub trait OrFail<T> {
+    fn or_fail(self, failure: Failure) -> Result<T, CliError>;
+}
+
+impl<T, E: fmt::Display> OrFail<T> for Result<T, E> {
+    fn or_fail(self, failure: Failure) -> Result<T, CliError> {
+        self.map_err(|e| CliError {
+            failure,
+            message: e.to_string(),
+            report: None,
+        })
+    }
+}
+
+/// Report of a command on a nanopub, printed with `--json`
+#[derive(Serialize, Debug, Default)]
+pub struct Report {
+    pub command: String,
+    #[serde(flatten)]
+    pub info: Option<NpInfo>,
+    pub check: Option<CheckResult>,
+    /// File the nanopub was read from, when processing several files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// File where the nanopub was written
+    pub file: Option<String>,
+    /// RDF of the nanopub, when it is not written to a file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rdf: Option<String>,
+    /// Reports on the referenced nanopubs fetched recursively
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<Report>,
+    /// Number of triples in each graph of the nanopub
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub graphs: Vec<GraphReport>,
+    /// Triples added and removed in the graphs that changed, compared to another nanopub
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Vec<GraphChanges>>,
+    /// Placeholders of a template, with the values they accept
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub placeholders: Vec<Placeholder>,
+    /// Profile shown or created, without its private key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileReport>,
+    /// Problems found when inspecting the nanopub, which do not make the command fail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
+    /// Number of files that passed or failed, when processing several files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
+    /// Reports on each file, when processing several files
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<Report>,
+    pub error: Option<String>,
+    pub exit_code: u8,
+}
+
+/// Graph of a nanopub, with its number of triples
+#[derive(Serialize, Debug, Clone)]
+pub struct GraphReport {
+    pub graph: String,
+    pub iri: String,
+    pub triples: usize,
+}
+
+/// Triples added and removed in a graph of a nanopub, in N-Triples
+#[derive(Serialize, Debug, Clone)]
+pub struct GraphChanges {
+    pub graph: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Public part of a profile
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfileReport {
+    pub orcid_id: Option<String>,
+    pub name: Option<String>,
+    pub introduction_nanopub_uri: Option<String>,
+    pub public_key: String,
+}
+
+impl From<&NpProfile> for ProfileReport {
+    fn from(profile: &NpProfile) -> Self {
+        Self {
+            orcid_id: profile.orcid_id.clone(),
+            name: profile.name.clone(),
+            introduction_nanopub_uri: profile.introduction_nanopub_uri.clone(),
+            public_key: profile.public_key.clone(),
+        }
+    }
+}
+
+/// Summary of a command run on several files
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct Summary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl Report {
+    pub fn new(command: &str, np: &Nanopub) -> Self {
+        Self {
+            command: command.to_string(),
+            info: Some(np.info.clone()),
+            ..Default::default()
+        }
+    }
+
+    pub fn print(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => println!("{json}"),
+            Err(e) => eprintln!("❌ Error serializing the report to JSON: {e}"),
+        }
+    }
+}
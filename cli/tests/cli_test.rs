@@ -0,0 +1,613 @@
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::{error::Error, fs};
+use tempfile::TempDir;
+
+const RESOURCES: &str = "../lib/tests/resources";
+const KEY: &str = "../lib/tests/resources/id_rsa";
+
+/// A temporary home directory for a test, with a copy of the unsigned test nanopub in `nanopub.trig`
+fn setup() -> (TempDir, PathBuf) {
+    let home = tempfile::tempdir().unwrap();
+    let np_file = home.path().join("nanopub.trig");
+    fs::copy(format!("{RESOURCES}/simple1-rsa.trig"), &np_file).unwrap();
+    (home, np_file)
+}
+
+/// Sign the nanopub file with the test key to `signed.<file name>`, and check the signed nanopub is valid
+fn sign_and_check(home: &Path, np_file: &Path) -> PathBuf {
+    let signed = np_file.with_file_name(format!(
+        "signed.{}",
+        np_file.file_name().unwrap().to_string_lossy()
+    ));
+    let output = np(home, &["sign", path(np_file), "-k", KEY], None);
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_valid(home, &signed);
+    signed
+}
+
+/// Check a nanopub file is valid with `np check`
+fn assert_valid(home: &Path, np_file: &Path) {
+    let output = np(home, &["check", path(np_file)], None);
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+}
+
+/// Write a profile with an ORCID and a name using the test key in `profile.yml`
+fn write_profile(home: &Path) -> PathBuf {
+    let profile_file = home.join("profile.yml");
+    let key = fs::canonicalize(KEY).unwrap();
+    let profile = format!(
+        "orcid_id: https://orcid.org/0000-0000-0000-0000\nname: Test Person\nprivate_key: {}\n",
+        key.display()
+    );
+    fs::write(&profile_file, profile).unwrap();
+    profile_file
+}
+
+fn path(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+/// Command running the CLI with the given home directory, so the config of the user is not used
+fn np_command(home: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_nanopub-cli"));
+    command
+        .env("HOME", home)
+        .env_remove("NANOPUB_PROFILE")
+        .env_remove("NANOPUB_KEY")
+        .env_remove("NANOPUB_SERVER");
+    command
+}
+
+/// Run the CLI with the given home directory
+fn np(home: &Path, args: &[&str], stdin: Option<&str>) -> Output {
+    run(np_command(home), args, stdin)
+}
+
+/// Run a command with the given arguments, and the content of its stdin
+fn run(mut command: Command, args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    if let Some(stdin) = stdin {
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+    }
+    child.wait_with_output().unwrap()
+}
+
+fn json_report(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn check_json_report() -> Result<(), Box<dyn Error>> {
+    let (home, _) = setup();
+    let output = np(
+        home.path(),
+        &[
+            "check",
+            &format!("{RESOURCES}/signed.simple1-rsa.trig"),
+            "--json",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report = json_report(&output);
+    assert_eq!(report["command"], "check");
+    assert_eq!(
+        report["uri"]["value"],
+        "https://w3id.org/np/RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4"
+    );
+    assert_eq!(
+        report["trusty_hash"],
+        "RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4"
+    );
+    assert_eq!(report["check"]["valid"], true);
+    assert_eq!(report["error"], Value::Null);
+    assert_eq!(report["exit_code"], 0);
+    Ok(())
+}
+
+#[test]
+fn exit_codes() -> Result<(), Box<dyn Error>> {
+    let (home, np_file) = setup();
+    let unsigned = path(&np_file);
+    let cases: [(Vec<&str>, i32); 5] = [
+        // The RDF cannot be parsed
+        (vec!["check", "../lib/tests/resources/wrong-rdf.trig"], 3),
+        // The signature does not match
+        (
+            vec![
+                "check",
+                "../lib/tests/testsuite/invalid/signed/simple1-invalid-rsa.trig",
+            ],
+            4,
+        ),
+        // The private key cannot be loaded
+        (vec!["sign", unsigned, "-k", "./inexistent/id_rsa"], 6),
+        // The output cannot be written
+        (
+            vec!["sign", unsigned, "-k", KEY, "-o", "./inexistent/np.trig"],
+            7,
+        ),
+        // The input cannot be read
+        (vec!["check", "./inexistent.trig"], 7),
+    ];
+    for (args, code) in cases {
+        let mut json_args = args.clone();
+        json_args.push("--json");
+        let output = np(home.path(), &json_args, None);
+        assert_eq!(output.status.code(), Some(code), "{args:?}");
+        let report = json_report(&output);
+        assert_eq!(report["command"], args[0]);
+        assert_eq!(report["exit_code"], code, "{args:?}");
+        assert!(report["error"].is_string(), "{args:?}");
+    }
+    // Invalid arguments are reported by clap
+    let output = np(home.path(), &["check", "--unknown"], None);
+    assert_eq!(output.status.code(), Some(2));
+    Ok(())
+}
+
+#[test]
+fn unsign_signed() -> Result<(), Box<dyn Error>> {
+    let (home, np_file) = setup();
+    let signed = sign_and_check(home.path(), &np_file);
+    let unsigned = home.path().join("unsigned.trig");
+    let output = np(
+        home.path(),
+        &["unsign", path(&signed), "-o", path(&unsigned), "--json"],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_eq!(json_report(&output)["trusty_hash"], "");
+    let np_rdf = fs::read_to_string(&unsigned)?;
+    assert!(!np_rdf.contains("npx:hasSignature"));
+    assert!(!np_rdf.contains("RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4"));
+    assert!(np_rdf.contains("ex:mosquito ex:transmits ex:malaria"));
+    Ok(())
+}
+
+#[test]
+fn intro_output() -> Result<(), Box<dyn Error>> {
+    let (home, _) = setup();
+    let profile_file = write_profile(home.path());
+    let intro = home.path().join("intro.trig");
+    let output = np(
+        home.path(),
+        &["intro", "-p", path(&profile_file), "-o", path(&intro)],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    assert_valid(home.path(), &intro);
+    assert!(fs::read_to_string(&intro)?.contains("orcid:0000-0000-0000-0000"));
+    Ok(())
+}
+
+#[test]
+fn sign_output_paths() -> Result<(), Box<dyn Error>> {
+    let (home, np_file) = setup();
+    let home = home.path();
+    let np_rdf = fs::read_to_string(&np_file)?;
+    let np_path = path(&np_file);
+
+    // Stored next to the input file, with a prefix by default
+    let signed = fs::read_to_string(sign_and_check(home, &np_file))?;
+
+    // Stored to the given output file
+    let output_file = home.join("out.trig");
+    let output = np(
+        home,
+        &["sign", np_path, "-k", KEY, "-o", path(&output_file)],
+        None,
+    );
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&output_file)?, signed);
+
+    // Read from stdin and written to stdout
+    let output = np(home, &["sign", "-", "-k", KEY], Some(&np_rdf));
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), signed.trim());
+
+    // The input file is overwritten in place
+    let output = np(home, &["sign", np_path, "-k", KEY, "--in-place"], None);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&np_file)?, signed);
+    // Both options cannot be used together
+    let output = np(
+        home,
+        &["sign", np_path, "-k", KEY, "--in-place", "-o", "x.trig"],
+        None,
+    );
+    assert!(!output.status.success());
+    Ok(())
+}
+
+/// Files checked in a batch run from the given directory, relative to it
+fn checked_files(dir: &Path, paths: &[&str]) -> Vec<String> {
+    let output = np_command(dir)
+        .current_dir(dir)
+        .arg("check")
+        .args(paths)
+        .arg("--json")
+        .output()
+        .unwrap();
+    let report = json_report(&output);
+    let mut files: Vec<String> = report["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["source"].as_str().unwrap().to_string())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn batch_globs() -> Result<(), Box<dyn Error>> {
+    let tmp = tempfile::tempdir()?;
+    let dir = tmp.path();
+    let np_rdf = fs::read_to_string(format!("{RESOURCES}/signed.simple1-rsa.trig"))?;
+    for file in [
+        "a.trig",
+        "b1.trig",
+        "sub/c.trig",
+        "sub/deep/d.trig",
+        ".hidden/e.trig",
+    ] {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, &np_rdf)?;
+    }
+    // Wildcards only match in their segment, and not the hidden files
+    assert_eq!(checked_files(dir, &["*.trig"]), vec!["a.trig", "b1.trig"]);
+    assert_eq!(
+        checked_files(dir, &["**/*.trig"]),
+        vec!["a.trig", "b1.trig", "sub/c.trig", "sub/deep/d.trig"]
+    );
+    assert_eq!(
+        checked_files(dir, &["sub/**/*.trig"]),
+        vec!["sub/c.trig", "sub/deep/d.trig"]
+    );
+    assert_eq!(checked_files(dir, &["b?.trig"]), vec!["b1.trig"]);
+    assert_eq!(checked_files(dir, &["[!a]*.trig"]), vec!["b1.trig"]);
+    assert_eq!(
+        checked_files(dir, &[".hidden/*.trig"]),
+        vec![".hidden/e.trig"]
+    );
+    // Absolute root
+    let pattern = format!("{}/sub/*.trig", dir.display());
+    assert_eq!(
+        checked_files(dir, &[&pattern]),
+        vec![format!("{}/sub/c.trig", dir.display())]
+    );
+    Ok(())
+}
+
+#[test]
+fn batch_sign_directory() -> Result<(), Box<dyn Error>> {
+    let tmp = tempfile::tempdir()?;
+    let dir = tmp.path();
+    let key = fs::canonicalize(KEY)?;
+    let np_rdf = fs::read_to_string(format!("{RESOURCES}/simple1-rsa.trig"))?;
+    fs::create_dir_all(dir.join("nanopubs/sub"))?;
+    fs::write(dir.join("nanopubs/a.trig"), &np_rdf)?;
+    fs::write(dir.join("nanopubs/sub/b.trig"), &np_rdf)?;
+    // Same name with another format, each is signed to its own file in its format
+    fs::copy(
+        format!("{RESOURCES}/nanopub.jsonld"),
+        dir.join("nanopubs/a.jsonld"),
+    )?;
+    // Hidden directories are skipped
+    fs::create_dir_all(dir.join("nanopubs/.hidden"))?;
+    fs::write(dir.join("nanopubs/.hidden/c.trig"), &np_rdf)?;
+    // Symbolic links to directories are not followed, so a loop does not recurse forever
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(dir.join("nanopubs"), dir.join("nanopubs/sub/loop"))?;
+
+    let sign = || {
+        np_command(dir)
+            .current_dir(dir)
+            .args(["sign", "nanopubs", "-k", path(&key), "--json"])
+            .output()
+            .unwrap()
+    };
+    let output = sign();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(json_report(&output)["summary"]["total"], 3);
+    assert!(dir.join("nanopubs/signed.a.trig").is_file());
+    assert!(dir.join("nanopubs/sub/signed.b.trig").is_file());
+    let signed_jsonld = fs::read_to_string(dir.join("nanopubs/signed.a.jsonld"))?;
+    assert!(serde_json::from_str::<Value>(&signed_jsonld).is_ok());
+    assert!(!dir.join("nanopubs/.hidden/signed.c.trig").exists());
+    // Running it again does not sign the signed files
+    let output = sign();
+    assert_eq!(json_report(&output)["summary"]["total"], 3);
+    assert!(!dir.join("nanopubs/signed.signed.a.trig").exists());
+    assert_eq!(checked_files(dir, &["nanopubs"]).len(), 6);
+
+    // Failures are printed to stderr, apart from the summary
+    fs::write(dir.join("nanopubs/wrong.trig"), "not RDF")?;
+    let output = np_command(dir)
+        .current_dir(dir)
+        .args(["check", "nanopubs"])
+        .output()?;
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8(output.stderr)?.contains("wrong.trig"));
+    assert!(!String::from_utf8(output.stdout)?.contains("wrong.trig"));
+    Ok(())
+}
+
+#[test]
+fn config_defaults() -> Result<(), Box<dyn Error>> {
+    let tmp = tempfile::tempdir()?;
+    let home = tmp.path();
+    let lib_dir = fs::canonicalize("../lib")?;
+    let np_rdf = fs::read_to_string(format!("{RESOURCES}/simple1-rsa.trig"))?;
+    let signed = format!("{RESOURCES}/signed.simple1-rsa.trig");
+    fs::create_dir_all(home.join(".nanopub"))?;
+    let config_file = home.join(".nanopub/config.toml");
+
+    // A malformed config only breaks the commands using it
+    fs::write(&config_file, "unknown = true\n")?;
+    assert_eq!(np(home, &["check", &signed], None).status.code(), Some(0));
+    let output = np(home, &["convert", &signed, "--to", "nq"], None);
+    assert_eq!(output.status.code(), Some(0));
+    let output = np(home, &["convert", &signed], None);
+    assert!(String::from_utf8(output.stderr)?.contains("Invalid config file"));
+
+    // The format of the config is used when --to is not given, it is required otherwise
+    fs::write(&config_file, "format = \"nquads\"\n")?;
+    let output = np(home, &["convert", &signed], None);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout)?.starts_with('<'));
+    fs::write(&config_file, "")?;
+    let output = np(home, &["convert", &signed], None);
+    assert_eq!(output.status.code(), Some(2));
+
+    // The profile from the environment is used instead of the key in the config
+    fs::write(&config_file, "key = \"/inexistent/id_rsa\"\n")?;
+    let output_file = home.join("signed.trig");
+    let args = ["sign", "-", "-o", path(&output_file)];
+    let mut command = np_command(home);
+    command.current_dir(&lib_dir);
+    assert_eq!(run(command, &args, Some(&np_rdf)).status.code(), Some(6));
+    let mut command = np_command(home);
+    command
+        .current_dir(&lib_dir)
+        .env("NANOPUB_PROFILE", "tests/resources/profile.yml");
+    assert_eq!(run(command, &args, Some(&np_rdf)).status.code(), Some(0));
+    assert!(home.join("signed.trig").is_file());
+    Ok(())
+}
+
+#[test]
+fn json_reports() -> Result<(), Box<dyn Error>> {
+    let (home, _) = setup();
+    let home = home.path();
+    let profile_file = write_profile(home);
+
+    // The public part of the profile, without the messages for humans
+    let output = np(
+        home,
+        &["profile", "show", "-p", path(&profile_file), "--json"],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report = json_report(&output);
+    assert_eq!(report["command"], "profile");
+    assert_eq!(
+        report["profile"]["orcid_id"],
+        "https://orcid.org/0000-0000-0000-0000"
+    );
+    assert!(report["profile"]["public_key"].is_string());
+    assert!(report["profile"].get("private_key").is_none());
+
+    // The placeholders of a template
+    let template = format!("{RESOURCES}/template-grouped.trig");
+    let output = np(home, &["template", "show", &template, "--json"], None);
+    assert_eq!(output.status.code(), Some(0));
+    let report = json_report(&output);
+    assert_eq!(report["command"], "template");
+    let placeholders = report["placeholders"].as_array().unwrap();
+    assert_eq!(placeholders.len(), 6);
+    assert!(placeholders.iter().any(|p| p["name"] == "status"
+        && p["possible_values"]
+            == json!(["http://example.org/Draft", "http://example.org/Final"])));
+
+    // The filled nanopub is in the report when it is not written to a file
+    let output = np(
+        home,
+        &[
+            "template",
+            "fill",
+            &template,
+            "-v",
+            "document=doc",
+            "-v",
+            "status=http://example.org/Final",
+            "-p",
+            path(&profile_file),
+            "--json",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let report = json_report(&output);
+    assert!(report["rdf"]
+        .as_str()
+        .unwrap()
+        .contains("sub:doc ex:hasStatus ex:Final"));
+    assert_eq!(report["file"], Value::Null);
+    Ok(())
+}
+
+#[test]
+fn diff_json_changes() -> Result<(), Box<dyn Error>> {
+    let (home, old) = setup();
+    let new = home.path().join("new.trig");
+    let np_rdf = fs::read_to_string(&old)?;
+    fs::write(&new, np_rdf.replace("ex:malaria", "ex:dengue"))?;
+    let output = np(
+        home.path(),
+        &["diff", path(&old), path(&new), "--json"],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let report = json_report(&output);
+    assert_eq!(report["command"], "diff");
+    let changes = report["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["graph"], "assertion");
+    assert_eq!(
+        changes[0]["added"],
+        json!([
+            "<http://example.org/mosquito> <http://example.org/transmits> <http://example.org/dengue>"
+        ])
+    );
+    assert_eq!(
+        changes[0]["removed"],
+        json!([
+            "<http://example.org/mosquito> <http://example.org/transmits> <http://example.org/malaria>"
+        ])
+    );
+
+    // Nanopubs with the same triples have no changes
+    let output = np(
+        home.path(),
+        &["diff", path(&old), path(&old), "--json"],
+        None,
+    );
+    assert_eq!(json_report(&output)["changes"], json!([]));
+    Ok(())
+}
+
+#[test]
+fn template_fill_output() -> Result<(), Box<dyn Error>> {
+    let (home, _) = setup();
+    let filled = home.path().join("filled.trig");
+    let output = np(
+        home.path(),
+        &[
+            "template",
+            "fill",
+            &format!("{RESOURCES}/template-grouped.trig"),
+            "-v",
+            "document=doc",
+            "-v",
+            "status=http://example.org/Draft",
+            "-v",
+            "author=https://orcid.org/0000-0000-0000-0000",
+            "-v",
+            "authorName=Test Person",
+            "-c",
+            "https://orcid.org/0000-0000-0000-0000",
+            "-o",
+            path(&filled),
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    let np_rdf = fs::read_to_string(&filled)?;
+    assert!(np_rdf.contains("\"Test Person\""));
+    assert!(np_rdf.contains("nt:wasCreatedFromTemplate"));
+    // The filled nanopub is unsigned, it is valid once signed
+    sign_and_check(home.path(), &filled);
+    Ok(())
+}
+
+#[test]
+fn retract_failures() -> Result<(), Box<dyn Error>> {
+    let (home, _) = setup();
+    let profile_file = write_profile(home.path());
+    let target = "https://w3id.org/np/RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4";
+    // Each case fails before the target is fetched or the retraction published
+    let cases: [(Vec<&str>, i32); 4] = [
+        // The URI of the nanopub to retract is missing
+        (vec!["retract", "--test", "-k", KEY], 2),
+        // The test server and another server cannot both be used
+        (
+            vec!["retract", target, "--test", "--server", "http://localhost"],
+            2,
+        ),
+        // The private key cannot be loaded
+        (
+            vec!["retract", target, "--test", "-k", "./inexistent/id_rsa"],
+            6,
+        ),
+        // The URI of the nanopub to retract is invalid
+        (
+            vec!["retract", "not a uri", "--test", "-p", path(&profile_file)],
+            3,
+        ),
+    ];
+    for (args, code) in cases {
+        let output = np(home.path(), &args, None);
+        assert_eq!(output.status.code(), Some(code), "{args:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn update_failures() -> Result<(), Box<dyn Error>> {
+    let (home, _) = setup();
+    let signed = format!("{RESOURCES}/signed.simple1-rsa.trig");
+    let wrong_rdf = format!("{RESOURCES}/wrong-rdf.trig");
+    let assertion = home.path().join("assertion.trig");
+    fs::write(
+        &assertion,
+        "<http://example.org/mosquito> <http://example.org/transmits> <http://example.org/dengue> .\n",
+    )?;
+    // Each case fails before the new version is published
+    let cases: [(Vec<&str>, i32); 4] = [
+        // The file of the new assertion is missing
+        (vec!["update", &signed, "--test", "-k", KEY], 2),
+        // The nanopub to update is invalid
+        (
+            vec![
+                "update",
+                &wrong_rdf,
+                "-a",
+                path(&assertion),
+                "--test",
+                "-k",
+                KEY,
+            ],
+            3,
+        ),
+        // The new assertion is invalid
+        (
+            vec!["update", &signed, "-a", &wrong_rdf, "--test", "-k", KEY],
+            3,
+        ),
+        // The new assertion cannot be read
+        (
+            vec![
+                "update",
+                &signed,
+                "-a",
+                "./inexistent.trig",
+                "--test",
+                "-k",
+                KEY,
+            ],
+            7,
+        ),
+    ];
+    for (args, code) in cases {
+        let output = np(home.path(), &args, None);
+        assert_eq!(output.status.code(), Some(code), "{args:?}");
+    }
+    Ok(())
+}
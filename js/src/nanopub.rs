@@ -1,6 +1,7 @@
 use js_sys::{Promise, JSON};
 use nanopub::{
-    constants::TEST_SERVER, get_np_server as get_server, profile::gen_keys, Nanopub as RsNanopub,
+    builder::parse_triple, constants::TEST_SERVER, get_np_server as get_server, nanopub::NpPart,
+    profile::gen_keys, Nanopub as RsNanopub, NanopubBuilder as RsNanopubBuilder,
     NpProfile as RsNpProfile, ProfileBuilder,
 };
 use serde::Serialize;
@@ -42,6 +43,35 @@ impl Nanopub {
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
+    #[wasm_bindgen]
+    pub fn head(&self) -> Result<JsValue, JsValue> {
+        self.triples(NpPart::Head)
+    }
+
+    #[wasm_bindgen]
+    pub fn assertion(&self) -> Result<JsValue, JsValue> {
+        self.triples(NpPart::Assertion)
+    }
+
+    #[wasm_bindgen]
+    pub fn provenance(&self) -> Result<JsValue, JsValue> {
+        self.triples(NpPart::Provenance)
+    }
+
+    #[wasm_bindgen]
+    pub fn pubinfo(&self) -> Result<JsValue, JsValue> {
+        self.triples(NpPart::PubInfo)
+    }
+
+    #[wasm_bindgen]
+    pub fn verify(&self) -> Result<JsValue, JsValue> {
+        let result = self
+            .np
+            .verify()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        serde_wasm_bindgen::to_value(&result).map_err(|e| e.into())
+    }
+
     #[wasm_bindgen]
     pub fn sign(self, profile: &NpProfile) -> Result<Nanopub, JsValue> {
         self.np
@@ -144,6 +174,25 @@ impl Nanopub {
     }
 }
 
+impl Nanopub {
+    /// Triples of a graph of the nanopub as arrays of terms, in N-Triples syntax
+    fn triples(&self, part: NpPart) -> Result<JsValue, JsValue> {
+        let triples: Vec<(String, String, String)> = self
+            .np
+            .graph(part)
+            .iter()
+            .map(|t| {
+                (
+                    t.subject.to_string(),
+                    t.predicate.to_string(),
+                    t.object.to_string(),
+                )
+            })
+            .collect();
+        serde_wasm_bindgen::to_value(&triples).map_err(|e| e.into())
+    }
+}
+
 /// Nanopub profile in JavaScript
 #[derive(TryFromJsValue)]
 #[wasm_bindgen]
@@ -199,10 +248,119 @@ impl NpProfile {
     }
 }
 
+/// Build a nanopub, terms are given as IRIs, blank nodes `_:id`, or N-Triples literals such as `"label"@en`
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct NanopubBuilder {
+    builder: RsNanopubBuilder,
+}
+
+#[wasm_bindgen]
+impl NanopubBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> NanopubBuilder {
+        Self::default()
+    }
+
+    /// IRI with the given local name in the temporary namespace of the nanopub
+    pub fn local(name: &str) -> String {
+        RsNanopubBuilder::local(name).into_string()
+    }
+
+    #[wasm_bindgen(js_name = addAssertion)]
+    pub fn add_assertion(
+        self,
+        subject: &str,
+        predicate: &str,
+        object: &str,
+    ) -> Result<NanopubBuilder, JsValue> {
+        let (s, p, o) = parse_triple(subject, predicate, object)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(Self {
+            builder: self.builder.add_assertion(s, p, o),
+        })
+    }
+
+    #[wasm_bindgen(js_name = addProvenance)]
+    pub fn add_provenance(
+        self,
+        subject: &str,
+        predicate: &str,
+        object: &str,
+    ) -> Result<NanopubBuilder, JsValue> {
+        let (s, p, o) = parse_triple(subject, predicate, object)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(Self {
+            builder: self.builder.add_provenance(s, p, o),
+        })
+    }
+
+    #[wasm_bindgen(js_name = addPubinfo)]
+    pub fn add_pubinfo(
+        self,
+        subject: &str,
+        predicate: &str,
+        object: &str,
+    ) -> Result<NanopubBuilder, JsValue> {
+        let (s, p, o) = parse_triple(subject, predicate, object)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(Self {
+            builder: self.builder.add_pubinfo(s, p, o),
+        })
+    }
+
+    #[wasm_bindgen(js_name = withCreator)]
+    pub fn with_creator(self, creator: &str) -> NanopubBuilder {
+        Self {
+            builder: self.builder.with_creator(creator),
+        }
+    }
+
+    /// Set the creation date, as an ISO 8601 string, e.g. `2024-01-01T00:00:00Z`
+    #[wasm_bindgen(js_name = withCreated)]
+    pub fn with_created(self, created: &str) -> Result<NanopubBuilder, JsValue> {
+        let created = created
+            .parse()
+            .map_err(|e| JsValue::from_str(&format!("Invalid creation date {created}: {e}")))?;
+        Ok(Self {
+            builder: self.builder.with_created(created),
+        })
+    }
+
+    #[wasm_bindgen(js_name = withLabel)]
+    pub fn with_label(self, label: &str) -> NanopubBuilder {
+        Self {
+            builder: self.builder.with_label(label),
+        }
+    }
+
+    #[wasm_bindgen(js_name = withType)]
+    pub fn with_type(self, np_type: &str) -> NanopubBuilder {
+        Self {
+            builder: self.builder.with_type(np_type),
+        }
+    }
+
+    #[wasm_bindgen(js_name = withPrefix)]
+    pub fn with_prefix(self, prefix: &str, namespace: &str) -> NanopubBuilder {
+        Self {
+            builder: self.builder.with_prefix(prefix, namespace),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn build(self) -> Result<Nanopub, JsValue> {
+        self.builder
+            .build()
+            .map(|np| Nanopub { np })
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
 /// Return a random server or the main nanopub server. Default to random server
 #[wasm_bindgen(js_name = getNpServer)]
 pub fn get_np_server(random: Option<bool>) -> String {
-    get_server(random.unwrap_or(true)).to_string()
+    get_server(random.unwrap_or(true))
 }
 
 #[wasm_bindgen(js_name = KeyPair)]
@@ -1,5 +1,5 @@
 import {describe, expect, test} from '@jest/globals';
-import {Nanopub, NpProfile} from "../pkg/node";
+import {Nanopub, NanopubBuilder, NpProfile} from "../pkg/node";
 
 const privKey=`MIICeAIBADANBgkqhkiG9w0BAQEFAASCAmIwggJeAgEAAoGBAPdEfIdHtZYoFh6/DWorzoHpFXMjugqW+CGpe9uk4BfUq54MToi2u7fgdGGtXLg4wsJFBYETdVeS0p1uA7EPe8LhwjHPktf5c6AZbO/lYpKM59e7/Ih4mvOy4iTIe/Dv+1OgasTSK0nXAbKUm/5iJ6LOYa82JQeE/QnT5gUw2e97AgMBAAECgYBbNQnyJINYpeSy5qoeFZaQ2Ncup2kCavmQASJMvJ5ka+/51nRJfY30n3iOZxIiad19J1SGbhUEfoXtyBzYfOubF2i2GJtdF5VyjdSoU6w/gOo2/vnbH+GCHnMclrWshohOADGQU/Y8pYhIvlQqcb6xEOts9m9C9g4uwvPXqjmhoQJBAPkmSFIZwF3i2UvJlHyeXi599L0jkGTUJy/Y4IjieUx5suwvAtG47ejhgIPKK06VtW49oGPHWjWc3cJAmnV+vTMCQQD+EPTvNtLpX9QiDEJD7b8woDwmVrvH/RUosP/cXpMQd7BUVgPlpffAlFJGDlOzwwjZjy+8kc6MYevh1kWqobSZAkEAyCs+nV99ErEHnYEFoB1oU3f0oeSpxKhCF4np03AIvi1kV6bpX+9wjNJnevp5UriqvDgc3S0zx7EQ5Vkb/1vkywJBAMMw59y4tAVT+DhITsi9aTvEfzG9RPt6trzSb2Aw0K/AJJpGkyvl/JfZ2/Oyoh/jYXM0DKrFIni76mtRIajcH1ECQQCJi6aXOaRkRPmf7FYY9cRaJdR1BtZkKZbDg6ZMD1bY97cGiM9STTMeldYcCtQBtyhVCTEObI/V6/0FAvY9Zi7w`;
 const orcid="https://orcid.org/0000-0000-0000-0000";
@@ -47,6 +47,29 @@ describe('Tests for the @nanopub/sign npm package', () => {
     expect(np.info().trusty_hash).toBe("RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4");
   });
 
+  test('nanopub info', async () => {
+    const info = new Nanopub(unsignedRdf).info();
+    expect(info.types).toEqual(["http://purl.org/nanopub/x/ExampleNanopub"]);
+    expect(info.creators).toEqual(["http://orcid.org/0000-0002-1267-0234"]);
+    expect(info.created.startsWith("2014-07-24T17:05:11")).toBe(true);
+  });
+
+  test('nanopub graphs', async () => {
+    const np = new Nanopub(unsignedRdf);
+    expect(np.assertion()).toEqual([["<http://example.org/mosquito>", "<http://example.org/transmits>", "<http://example.org/malaria>"]]);
+    expect(np.head().length).toBe(4);
+    expect(np.provenance().length).toBe(1);
+  });
+
+  test('verify nanopub', async () => {
+    const profile = new NpProfile(privKey, orcid, "Your Name");
+    const result = new Nanopub(unsignedRdf).sign(profile).verify();
+    expect(result.valid).toBe(true);
+    expect(result.trusty).toBe(true);
+    expect(result.signed).toBe(true);
+    expect(result.algorithm).toBe("RSA");
+  });
+
   test('sign nanopub and check', async () => {
     const profile = new NpProfile(privKey, orcid, "Your Name");
     let np = new Nanopub(unsignedRdf);
@@ -81,4 +104,17 @@ describe('Tests for the @nanopub/sign npm package', () => {
     expect(np.info().published).toBeDefined();
   });
 
+  test('build nanopub', async () => {
+    const profile = new NpProfile(privKey, orcid, "Your Name");
+    const np = new NanopubBuilder()
+      .addAssertion("_:claim", "http://www.w3.org/1999/02/22-rdf-syntax-ns#value", '"42"')
+      .addProvenance(NanopubBuilder.local("assertion"), "http://www.w3.org/ns/prov#wasAttributedTo", orcid)
+      .withCreator(orcid)
+      .withCreated("2024-01-01T00:00:00Z")
+      .withLabel("The answer")
+      .build()
+      .sign(profile);
+    expect(np.info().trusty_hash.startsWith("RA")).toBe(true);
+  });
+
 });
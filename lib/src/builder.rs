@@ -0,0 +1,221 @@
+use crate::constants::NP_TEMP_URI;
+use crate::error::NpError;
+use crate::nanopub::{create_base_dataset, Nanopub};
+use crate::utils::Namespace;
+use crate::vocab::{dct, npx};
+
+use chrono::{DateTime, Utc};
+use oxrdf::{
+    vocab::{rdfs, xsd},
+    BlankNode, GraphName, Literal, NamedNode, NamedOrBlankNode, Quad, Term,
+};
+use std::str::FromStr;
+
+/// Build a nanopub from code, adding triples to its assertion, provenance and pubinfo graphs.
+///
+/// The nanopub uses the temporary namespace until it is signed, use [`NanopubBuilder::local`]
+/// to create IRIs in this namespace, and blank nodes for nodes that should get a local name when signing.
+///
+/// # Examples
+///
+/// ```
+/// use nanopub::NanopubBuilder;
+/// use nanopub::vocab::prov;
+/// use oxrdf::{vocab::rdf, Literal, NamedNode};
+///
+/// let orcid = NamedNode::new("https://orcid.org/0000-0000-0000-0000").unwrap();
+/// let np = NanopubBuilder::new()
+///     .add_assertion(NanopubBuilder::local("claim"), rdf::VALUE, Literal::new_simple_literal("42"))
+///     .add_provenance(NanopubBuilder::local("assertion"), prov::WAS_ATTRIBUTED_TO, orcid.clone())
+///     .with_creator(orcid.as_str())
+///     .with_label("The answer")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NanopubBuilder {
+    quads: Vec<Quad>,
+    creators: Vec<String>,
+    created: Option<DateTime<Utc>>,
+    labels: Vec<String>,
+    types: Vec<String>,
+    prefixes: Vec<(String, String)>,
+}
+
+impl NanopubBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// IRI of the nanopub in the temporary namespace, replaced by its trusty URI when signing
+    pub fn uri() -> NamedNode {
+        NamedNode::new_unchecked(NP_TEMP_URI)
+    }
+
+    /// IRI with the given local name in the temporary namespace of the nanopub, e.g. `assertion`
+    pub fn local(name: &str) -> NamedNode {
+        Namespace(NP_TEMP_URI.to_string()).get(name)
+    }
+
+    /// Add a triple to the assertion graph
+    pub fn add_assertion(
+        self,
+        subject: impl Into<NamedOrBlankNode>,
+        predicate: impl Into<NamedNode>,
+        object: impl Into<Term>,
+    ) -> Self {
+        self.add_to_graph("assertion", subject, predicate, object)
+    }
+
+    /// Add a triple to the provenance graph
+    pub fn add_provenance(
+        self,
+        subject: impl Into<NamedOrBlankNode>,
+        predicate: impl Into<NamedNode>,
+        object: impl Into<Term>,
+    ) -> Self {
+        self.add_to_graph("provenance", subject, predicate, object)
+    }
+
+    /// Add a triple to the pubinfo graph
+    pub fn add_pubinfo(
+        self,
+        subject: impl Into<NamedOrBlankNode>,
+        predicate: impl Into<NamedNode>,
+        object: impl Into<Term>,
+    ) -> Self {
+        self.add_to_graph("pubinfo", subject, predicate, object)
+    }
+
+    /// Add a creator of the nanopub (`dct:creator`), usually an ORCID
+    pub fn with_creator(mut self, creator: &str) -> Self {
+        self.creators.push(creator.to_string());
+        self
+    }
+
+    /// Set the creation date of the nanopub (`dct:created`), defaults to the date of signing
+    pub fn with_created(mut self, created: DateTime<Utc>) -> Self {
+        self.created = Some(created);
+        self
+    }
+
+    /// Add a label to the nanopub (`rdfs:label`)
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.labels.push(label.to_string());
+        self
+    }
+
+    /// Add a type to the nanopub (`npx:hasNanopubType`)
+    pub fn with_type(mut self, np_type: &str) -> Self {
+        self.types.push(np_type.to_string());
+        self
+    }
+
+    /// Add a prefix used when serializing the nanopub
+    pub fn with_prefix(mut self, prefix: &str, namespace: &str) -> Self {
+        self.prefixes
+            .push((prefix.to_string(), namespace.to_string()));
+        self
+    }
+
+    /// Build the nanopub, and check it is valid
+    pub fn build(self) -> Result<Nanopub, NpError> {
+        let mut dataset = create_base_dataset()?;
+        let np_node = Self::uri();
+        let pubinfo_graph = GraphName::from(Self::local("pubinfo"));
+        for creator in &self.creators {
+            dataset.insert(&Quad::new(
+                np_node.clone(),
+                dct::CREATOR,
+                parse_iri(creator)?,
+                pubinfo_graph.clone(),
+            ));
+        }
+        if let Some(created) = &self.created {
+            dataset.insert(&Quad::new(
+                np_node.clone(),
+                dct::CREATED,
+                Literal::new_typed_literal(
+                    created.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+                    xsd::DATE_TIME,
+                ),
+                pubinfo_graph.clone(),
+            ));
+        }
+        for label in &self.labels {
+            dataset.insert(&Quad::new(
+                np_node.clone(),
+                rdfs::LABEL,
+                Literal::new_simple_literal(label),
+                pubinfo_graph.clone(),
+            ));
+        }
+        for np_type in &self.types {
+            dataset.insert(&Quad::new(
+                np_node.clone(),
+                npx::HAS_NANOPUB_TYPE,
+                parse_iri(np_type)?,
+                pubinfo_graph.clone(),
+            ));
+        }
+        for quad in &self.quads {
+            dataset.insert(quad);
+        }
+        let mut np = Nanopub::new(dataset)?;
+        np.is_valid()?;
+        np.info.prefixes.extend(self.prefixes);
+        Ok(np)
+    }
+
+    fn add_to_graph(
+        mut self,
+        graph: &str,
+        subject: impl Into<NamedOrBlankNode>,
+        predicate: impl Into<NamedNode>,
+        object: impl Into<Term>,
+    ) -> Self {
+        self.quads
+            .push(Quad::new(subject, predicate, object, Self::local(graph)));
+        self
+    }
+}
+
+/// Parse a triple given as strings, see [`parse_term`] for the syntax of each term
+pub fn parse_triple(
+    subject: &str,
+    predicate: &str,
+    object: &str,
+) -> Result<(NamedOrBlankNode, NamedNode, Term), NpError> {
+    let subject = NamedOrBlankNode::try_from(parse_term(subject)?).map_err(|_| {
+        NpError(format!(
+            "Invalid subject {subject}: literals are not allowed"
+        ))
+    })?;
+    Ok((subject, parse_iri(predicate)?, parse_term(object)?))
+}
+
+/// Parse a term given as a string: an IRI, optionally between `<>`, a blank node `_:id`,
+/// or a literal in N-Triples syntax, e.g. `"label"@en` or `"42"^^<http://www.w3.org/2001/XMLSchema#integer>`
+pub fn parse_term(term: &str) -> Result<Term, NpError> {
+    let term = term.trim();
+    if let Some(id) = term.strip_prefix("_:") {
+        return BlankNode::new(id)
+            .map(Term::from)
+            .map_err(|e| NpError(format!("Invalid blank node {term}: {e}")));
+    }
+    if term.starts_with('"') {
+        return Literal::from_str(term)
+            .map(Term::from)
+            .map_err(|e| NpError(format!("Invalid literal {term}: {e}")));
+    }
+    parse_iri(term).map(Term::from)
+}
+
+/// Parse an IRI, optionally between `<>`
+fn parse_iri(iri: &str) -> Result<NamedNode, NpError> {
+    let iri = iri
+        .strip_prefix('<')
+        .and_then(|iri| iri.strip_suffix('>'))
+        .unwrap_or(iri);
+    NamedNode::new(iri).map_err(|e| NpError(format!("Invalid IRI {iri}: {e}")))
+}
@@ -0,0 +1,117 @@
+use crate::error::NpError;
+use crate::nanopub::Nanopub;
+use crate::store::NanopubStore;
+use crate::utils::extract_artifact_code;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// A cache of trusty nanopubs used in front of [`Nanopub::fetch`], keyed by artifact code.
+///
+/// Implementations must only store nanopubs that passed the trusty verification.
+pub trait NpCache: Send + Sync {
+    /// Get a nanopub from the cache given its URI or artifact code, `None` if not cached
+    fn get(&self, uri: &str) -> Result<Option<Nanopub>, NpError>;
+    /// Add a trusty nanopub to the cache, it is checked before being stored
+    fn put(&self, np: &Nanopub) -> Result<(), NpError>;
+}
+
+/// In-memory cache keeping the `capacity` most recently used nanopubs
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    entries: Mutex<MemoryCacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryCacheEntries {
+    nanopubs: HashMap<String, Nanopub>,
+    // Artifact codes from least to most recently used
+    usage: VecDeque<String>,
+}
+
+impl MemoryCacheEntries {
+    fn touch(&mut self, code: &str) {
+        if let Some(pos) = self.usage.iter().position(|c| c == code) {
+            self.usage.remove(pos);
+        }
+        self.usage.push_back(code.to_string());
+    }
+}
+
+impl MemoryCache {
+    /// Create an in-memory cache holding at most `capacity` nanopubs
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(MemoryCacheEntries::default()),
+        }
+    }
+
+    /// Number of nanopubs currently in the cache
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.nanopubs.len())
+            .unwrap_or(0)
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl NpCache for MemoryCache {
+    fn get(&self, uri: &str) -> Result<Option<Nanopub>, NpError> {
+        let Some(code) = extract_artifact_code(uri) else {
+            return Ok(None);
+        };
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|e| NpError(format!("Failed to lock the cache: {e}")))?;
+        let np = entries.nanopubs.get(code).cloned();
+        if np.is_some() {
+            entries.touch(code);
+        }
+        Ok(np)
+    }
+
+    fn put(&self, np: &Nanopub) -> Result<(), NpError> {
+        if np.info.trusty_hash.is_empty() {
+            return Err(NpError(format!(
+                "Only trusty nanopubs can be cached, no trusty hash found in {}",
+                np.info.uri
+            )));
+        }
+        let np = np.clone().check()?;
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|e| NpError(format!("Failed to lock the cache: {e}")))?;
+        let code = np.info.trusty_hash.clone();
+        entries.touch(&code);
+        entries.nanopubs.insert(code, np);
+        while entries.nanopubs.len() > self.capacity {
+            let Some(oldest) = entries.usage.pop_front() else {
+                break;
+            };
+            entries.nanopubs.remove(&oldest);
+        }
+        Ok(())
+    }
+}
+
+impl NpCache for NanopubStore {
+    fn get(&self, uri: &str) -> Result<Option<Nanopub>, NpError> {
+        NanopubStore::get(self, uri)
+    }
+
+    fn put(&self, np: &Nanopub) -> Result<(), NpError> {
+        NanopubStore::put(self, np).map(|_| ())
+    }
+}
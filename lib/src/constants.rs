@@ -1,4 +1,4 @@
-/// List of available Nanopub servers
+/// List of available Nanopub servers, used when the list could not be loaded from the monitor
 /// Checkout the live list at https://monitor.np.trustyuri.net/
 pub const LIST_SERVERS: [&str; 3] = [
     "https://registry.np.trustyuri.net/",
@@ -6,6 +6,9 @@ pub const LIST_SERVERS: [&str; 3] = [
     "https://registry.petapico.org/",
 ];
 
+/// Nanopub monitor URL, providing the list of servers and their health in JSON
+pub const MONITOR_URL: &str = "https://monitor.np.trustyuri.net/?format=json";
+
 /// Nanopub test server URL
 pub const TEST_SERVER: &str = "https://test.registry.knowledgepixels.com/";
 // pub const TEST_SERVER_GRLC: &str = "https://grlc.test.nps.knowledgepixels.com/api/local/local/";
@@ -15,6 +18,3 @@ pub const NP_TEMP_URI: &str = "http://purl.org/nanopub/temp/";
 // pub const NP_TEMP_URI: &str = "http://purl.org/nanopub/temp/mynanopub#";
 
 pub const DEFAULT_NP_PROFILE: &str = "~/.nanopub/profile.yml";
-
-pub const BOLD: &str = "\x1b[1;96m";
-pub const END: &str = "\x1b[0m";
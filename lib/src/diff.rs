@@ -0,0 +1,168 @@
+use crate::nanopub::{Nanopub, NpPart};
+use crate::sign::number_bnodes;
+
+use oxrdf::{
+    BlankNode, BlankNodeRef, Dataset, NamedNode, NamedNodeRef, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Quad, Term, TermRef, Triple,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The graphs of a nanopub, in the order they are compared
+const PARTS: [NpPart; 4] = [
+    NpPart::Head,
+    NpPart::Assertion,
+    NpPart::Provenance,
+    NpPart::PubInfo,
+];
+
+/// Triples added and removed in a graph of a nanopub
+#[derive(Clone, Debug)]
+pub struct GraphDiff {
+    pub part: NpPart,
+    /// Triples only in the new nanopub, sorted
+    pub added: Vec<Triple>,
+    /// Triples only in the old nanopub, sorted
+    pub removed: Vec<Triple>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Changes between two nanopubs, graph by graph.
+///
+/// # Examples
+///
+/// ```
+/// use std::fs;
+/// use nanopub::{diff::NpDiff, Nanopub};
+///
+/// let old = Nanopub::new(&fs::read_to_string("./tests/resources/simple1-rsa.trig").unwrap()).unwrap();
+/// let new = Nanopub::new(&fs::read_to_string("./tests/resources/signed.simple1-rsa.trig").unwrap()).unwrap();
+/// let diff = NpDiff::new(&old, &new);
+/// assert!(diff.graph(nanopub::nanopub::NpPart::Assertion).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct NpDiff {
+    /// Changes in the head, assertion, provenance, and pubinfo graphs
+    pub graphs: Vec<GraphDiff>,
+}
+
+impl NpDiff {
+    /// Compare an old and a new nanopub, graph by graph.
+    ///
+    /// IRIs in the namespace of the old nanopub are moved to the namespace of the new one,
+    /// so that a different trusty URI does not show as a change. Blank nodes are matched by the triples they appear in.
+    pub fn new(old: &Nanopub, new: &Nanopub) -> Self {
+        let old_graphs = normalized_graphs(old, new);
+        let new_graphs = normalized_graphs(new, new);
+        let graphs = PARTS
+            .into_iter()
+            .zip(old_graphs.into_iter().zip(new_graphs))
+            .map(|(part, (old_triples, new_triples))| GraphDiff {
+                part,
+                added: sorted(new_triples.difference(&old_triples)),
+                removed: sorted(old_triples.difference(&new_triples)),
+            })
+            .collect();
+        Self { graphs }
+    }
+
+    /// Get the changes in a graph
+    pub fn graph(&self, part: NpPart) -> &GraphDiff {
+        self.graphs
+            .iter()
+            .find(|graph| graph.part == part)
+            .expect("all graphs are compared")
+    }
+
+    /// True when the nanopubs have the same triples in all their graphs
+    pub fn is_empty(&self) -> bool {
+        self.graphs.iter().all(GraphDiff::is_empty)
+    }
+}
+
+impl fmt::Display for NpDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for graph in self.graphs.iter().filter(|graph| !graph.is_empty()) {
+            writeln!(f, "{:?}", graph.part)?;
+            for triple in &graph.removed {
+                writeln!(f, "- {triple}")?;
+            }
+            for triple in &graph.added {
+                writeln!(f, "+ {triple}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Triples of each graph of the nanopub, with the IRIs of its namespace moved to the namespace of the target nanopub,
+/// and the blank nodes numbered from the triples they appear in
+fn normalized_graphs(np: &Nanopub, target: &Nanopub) -> Vec<HashSet<Triple>> {
+    let uri = np.info.uri.as_str();
+    let ns = np.info.ns.as_str();
+    let move_iri = |node: NamedNodeRef| -> NamedNode {
+        let iri = node.as_str();
+        // The namespace alone is used as the nanopub URI too, as when signing
+        if iri == uri || iri == ns {
+            target.info.uri.clone()
+        } else if let Some(local) = iri.strip_prefix(ns) {
+            NamedNode::new_unchecked(format!("{}{local}", target.info.ns.as_str()))
+        } else {
+            node.into_owned()
+        }
+    };
+    // Move the triples to the graphs of the target first, so that blank nodes are numbered the same way in both nanopubs
+    let mut dataset = Dataset::new();
+    for part in PARTS {
+        for triple in np.graph(part).iter() {
+            let subject: NamedOrBlankNode = match triple.subject {
+                NamedOrBlankNodeRef::NamedNode(node) => move_iri(node).into(),
+                subject => subject.into_owned(),
+            };
+            let object: Term = match triple.object {
+                TermRef::NamedNode(node) => move_iri(node).into(),
+                object => object.into_owned(),
+            };
+            dataset.insert(&Quad::new(
+                subject,
+                move_iri(triple.predicate),
+                object,
+                target.graph_iri(part).clone(),
+            ));
+        }
+    }
+    let bnodes: HashMap<String, usize> = number_bnodes(&dataset);
+    let bnode =
+        |node: BlankNodeRef| BlankNode::new_unchecked(format!("b{}", bnodes[node.as_str()]));
+    PARTS
+        .into_iter()
+        .map(|part| {
+            dataset
+                .graph(target.graph_iri(part))
+                .iter()
+                .map(|triple| {
+                    let subject: NamedOrBlankNode = match triple.subject {
+                        NamedOrBlankNodeRef::BlankNode(node) => bnode(node).into(),
+                        subject => subject.into_owned(),
+                    };
+                    let object: Term = match triple.object {
+                        TermRef::BlankNode(node) => bnode(node).into(),
+                        object => object.into_owned(),
+                    };
+                    Triple::new(subject, triple.predicate, object)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn sorted<'a>(triples: impl Iterator<Item = &'a Triple>) -> Vec<Triple> {
+    let mut triples: Vec<Triple> = triples.cloned().collect();
+    triples.sort_by_cached_key(|triple| triple.to_string());
+    triples
+}
@@ -1,14 +1,17 @@
 use crate::constants::{NP_PREF_NS, NP_TEMP_URI};
 use crate::error::NpError;
-use crate::utils::Namespace;
+use crate::utils::{term_value, Namespace};
 use crate::vocab::{dct, np, npx, pav, prov};
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use oxrdf::{
-    vocab::rdf, Dataset, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, TermRef,
+    vocab::{rdf, rdfs},
+    Dataset, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, TermRef,
 };
 use regex::Regex;
 use serde::Serialize;
 use std::fmt;
+use std::str::FromStr;
 
 /// Infos extracted from a nanopublication: graphs URLs, signature, trusty hash...
 #[derive(Clone, Serialize, Debug)]
@@ -30,6 +33,24 @@ pub struct NpInfo {
     pub algo: String,
     pub public_key: String,
     pub orcid: String,
+    /// All creators of the nanopub: `dct:creator`, `pav:createdBy`, and `prov:wasAttributedTo`
+    pub creators: Vec<String>,
+    /// Authors of the nanopub: `pav:authoredBy`
+    pub authors: Vec<String>,
+    /// Creation date: `dct:created`, `prov:generatedAtTime`, or `pav:createdOn`
+    pub created: Option<DateTime<Utc>>,
+    /// Types of the nanopub: `npx:hasNanopubType`, and `rdf:type` in the pubinfo
+    pub types: Vec<String>,
+    /// Labels of the nanopub: `rdfs:label` in the pubinfo
+    pub labels: Vec<String>,
+    /// Resources introduced by the nanopub: `npx:introduces`
+    pub introduces: Vec<String>,
+    /// Resources embedded in the nanopub: `npx:embeds`
+    pub embeds: Vec<String>,
+    /// Previous versions replaced by the nanopub: `npx:supersedes`
+    pub supersedes: Vec<String>,
+    /// Nanopubs retracted in the assertion: `npx:retracts`
+    pub retracts: Vec<String>,
     pub published: Option<String>,
 }
 
@@ -40,6 +61,12 @@ impl fmt::Display for NpInfo {
         writeln!(f, "Base URI: {}", self.base_uri)?;
         writeln!(f, "Trusty Hash: {}", self.trusty_hash)?;
         writeln!(f, "ORCID: {}", self.orcid)?;
+        if let Some(created) = &self.created {
+            writeln!(f, "Created: {}", created.to_rfc3339())?;
+        }
+        if !self.types.is_empty() {
+            writeln!(f, "Types: {}", self.types.join(", "))?;
+        }
         writeln!(f, "Head Graph: {}", self.head.as_str())?;
         writeln!(f, "Assertion Graph: {}", self.assertion.as_str())?;
         Ok(())
@@ -178,7 +205,7 @@ pub fn extract_np_info(
     } else if original_ns.starts_with(NP_TEMP_URI) {
         NP_PREF_NS.to_string()
     } else if !original_ns.ends_with(['#', '/', '.']) {
-        format!("{}.", &original_ns)
+        format!("{original_ns}.")
     } else {
         original_ns.to_string()
     };
@@ -249,6 +276,44 @@ pub fn extract_np_info(
         None => None,
     };
 
+    // Extract metadata about the nanopub in the pubinfo
+    let ns_subject_term = NamedOrBlankNodeRef::from(NamedNodeRef::new_unchecked(original_ns));
+    let np_values = |predicates: &[NamedNodeRef<'_>]| -> Vec<String> {
+        let mut values: Vec<String> = pubinfo_graph
+            .iter()
+            .filter(|x| {
+                (x.subject == np_subject_term || x.subject == ns_subject_term)
+                    && predicates.contains(&x.predicate)
+            })
+            .filter_map(|x| term_value(x.object))
+            .collect();
+        values.sort();
+        values.dedup();
+        values
+    };
+    let creators = np_values(&[dct::CREATOR, pav::CREATED_BY, prov::WAS_ATTRIBUTED_TO]);
+    let authors = np_values(&[pav::AUTHORED_BY]);
+    let mut types = np_values(&[npx::HAS_NANOPUB_TYPE, rdf::TYPE]);
+    types.retain(|t| t != np::NANOPUBLICATION.as_str());
+    let labels = np_values(&[rdfs::LABEL]);
+    let introduces = np_values(&[npx::INTRODUCES]);
+    let embeds = np_values(&[npx::EMBEDS]);
+    let supersedes = np_values(&[npx::SUPERSEDES]);
+    let created = [dct::CREATED, prov::GENERATED_AT_TIME, pav::CREATED_ON]
+        .iter()
+        .find_map(|predicate| {
+            np_values(&[*predicate])
+                .first()
+                .and_then(|d| parse_datetime(d))
+        });
+    let mut retracts: Vec<String> = dataset
+        .graph(assertion_iri.as_ref())
+        .triples_for_predicate(npx::RETRACTS)
+        .filter_map(|x| term_value(x.object))
+        .collect();
+    retracts.sort();
+    retracts.dedup();
+
     Ok(NpInfo {
         uri: np_iri,
         ns: np_ns,
@@ -267,6 +332,31 @@ pub fn extract_np_info(
         public_key: pubkey.unwrap_or("".to_string()),
         algo: algo.unwrap_or("".to_string()),
         orcid: orcid.unwrap_or("".to_string()),
+        creators,
+        authors,
+        created,
+        types,
+        labels,
+        introduces,
+        embeds,
+        supersedes,
+        retracts,
         published: None,
     })
 }
+
+/// Parse a `xsd:dateTime`, or a `xsd:date` at midnight, dates without timezone are considered UTC
+fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::from_str(value).map(|date| date.and_utc()))
+        .ok()
+        .or_else(|| {
+            // A xsd:date is a day, optionally followed by its timezone
+            let (day, timezone) = value.split_at_checked(10)?;
+            let timezone = if timezone.is_empty() { "Z" } else { timezone };
+            DateTime::parse_from_rfc3339(&format!("{day}T00:00:00{timezone}"))
+                .map(|date| date.with_timezone(&Utc))
+                .ok()
+        })
+}
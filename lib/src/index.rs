@@ -0,0 +1,326 @@
+use crate::cache::NpCache;
+use crate::error::NpError;
+use crate::nanopub::Nanopub;
+use crate::profile::NpProfile;
+use crate::vocab::{dct, npx};
+use crate::NanopubBuilder;
+
+use oxrdf::{vocab::rdf, Literal, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, TermRef};
+use std::collections::HashSet;
+
+/// Maximum number of elements and sub-indexes in one index nanopub, as in nanopub-java.
+/// Bigger indexes are split in several index nanopubs, each appending the previous one.
+pub const MAX_INDEX_SIZE: usize = 1000;
+
+/// Title from the DC elements vocabulary, used by older indexes
+const DC_TITLE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://purl.org/dc/elements/1.1/title");
+
+/// Build index nanopubs listing nanopubs (`npx:includesElement`) and other indexes (`npx:hasSubIndex`).
+///
+/// # Examples
+///
+/// ```
+/// use std::fs;
+/// use nanopub::{index::IndexBuilder, ProfileBuilder};
+///
+/// let profile = ProfileBuilder::new(fs::read_to_string("./tests/resources/id_rsa").unwrap())
+///     .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+///     .build().unwrap();
+/// let indexes = IndexBuilder::new()
+///     .add_element("https://w3id.org/np/RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw")
+///     .with_title("My nanopubs")
+///     .build(&profile)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct IndexBuilder {
+    elements: Vec<String>,
+    sub_indexes: Vec<String>,
+    appends: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    creators: Vec<String>,
+    max_size: usize,
+}
+
+impl Default for IndexBuilder {
+    fn default() -> Self {
+        Self {
+            elements: Vec::new(),
+            sub_indexes: Vec::new(),
+            appends: None,
+            title: None,
+            description: None,
+            creators: Vec::new(),
+            max_size: MAX_INDEX_SIZE,
+        }
+    }
+}
+
+impl IndexBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a nanopub to the index
+    pub fn add_element(mut self, uri: &str) -> Self {
+        self.elements.push(uri.to_string());
+        self
+    }
+
+    /// Add nanopubs to the index
+    pub fn add_elements<S: AsRef<str>>(mut self, uris: impl IntoIterator<Item = S>) -> Self {
+        self.elements
+            .extend(uris.into_iter().map(|uri| uri.as_ref().to_string()));
+        self
+    }
+
+    /// Add an index whose elements are part of this index
+    pub fn add_sub_index(mut self, uri: &str) -> Self {
+        self.sub_indexes.push(uri.to_string());
+        self
+    }
+
+    /// Extend an existing index, the first index nanopub created appends it
+    pub fn appends_index(mut self, uri: &str) -> Self {
+        self.appends = Some(uri.to_string());
+        self
+    }
+
+    /// Set the title of the index (`dct:title`)
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Set the description of the index (`dct:description`)
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Add a creator of the index (`dct:creator`), defaults to the ORCID of the profile
+    pub fn with_creator(mut self, creator: &str) -> Self {
+        self.creators.push(creator.to_string());
+        self
+    }
+
+    /// Set the maximum number of elements and sub-indexes per index nanopub, defaults to [`MAX_INDEX_SIZE`]
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size.max(1);
+        self
+    }
+
+    /// Build and sign the index nanopubs.
+    ///
+    /// Returns the index nanopubs in the order they need to be published,
+    /// the last one is the complete index, the others are typed `npx:IncompleteIndex`.
+    pub fn build(self, profile: &NpProfile) -> Result<Vec<Nanopub>, NpError> {
+        let mut entries: Vec<(NamedNodeRef, NamedNode)> = Vec::new();
+        for uri in &self.sub_indexes {
+            entries.push((npx::HAS_SUB_INDEX, parse_uri(uri)?));
+        }
+        for uri in &self.elements {
+            entries.push((npx::INCLUDES_ELEMENT, parse_uri(uri)?));
+        }
+        if entries.is_empty() {
+            return Err(NpError(
+                "An index needs at least one element or sub-index".to_string(),
+            ));
+        }
+        let chunks: Vec<_> = entries.chunks(self.max_size).collect();
+        let mut indexes: Vec<Nanopub> = Vec::with_capacity(chunks.len());
+        let mut previous = self.appends.as_deref().map(parse_uri).transpose()?;
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut builder = NanopubBuilder::new()
+                .add_provenance(
+                    NanopubBuilder::local("assertion"),
+                    rdf::TYPE,
+                    npx::INDEX_ASSERTION,
+                )
+                .add_pubinfo(NanopubBuilder::uri(), rdf::TYPE, npx::NANOPUB_INDEX);
+            for (predicate, uri) in chunk.iter() {
+                builder = builder.add_assertion(NanopubBuilder::uri(), *predicate, uri.clone());
+            }
+            if let Some(previous) = previous {
+                builder =
+                    builder.add_assertion(NanopubBuilder::uri(), npx::APPENDS_INDEX, previous);
+            }
+            if i + 1 < chunks.len() {
+                builder =
+                    builder.add_pubinfo(NanopubBuilder::uri(), rdf::TYPE, npx::INCOMPLETE_INDEX);
+            } else {
+                if let Some(title) = &self.title {
+                    builder = builder.add_pubinfo(
+                        NanopubBuilder::uri(),
+                        dct::TITLE,
+                        Literal::new_simple_literal(title),
+                    );
+                }
+                if let Some(description) = &self.description {
+                    builder = builder.add_pubinfo(
+                        NanopubBuilder::uri(),
+                        dct::DESCRIPTION,
+                        Literal::new_simple_literal(description),
+                    );
+                }
+            }
+            for creator in &self.creators {
+                builder = builder.with_creator(creator);
+            }
+            let np = builder.build()?.sign(profile)?;
+            previous = Some(np.info.uri.clone());
+            indexes.push(np);
+        }
+        Ok(indexes)
+    }
+}
+
+/// A nanopub index, listing nanopubs and other indexes
+#[derive(Clone, Debug)]
+pub struct NpIndex {
+    pub np: Nanopub,
+    /// Nanopubs directly included in this index nanopub (`npx:includesElement`)
+    pub elements: Vec<String>,
+    /// Indexes whose elements are part of this index (`npx:hasSubIndex`)
+    pub sub_indexes: Vec<String>,
+    /// The index extended by this index nanopub (`npx:appendsIndex`)
+    pub appends: Option<String>,
+    /// False for the `npx:IncompleteIndex` created when splitting a big index
+    pub complete: bool,
+    pub title: Option<String>,
+}
+
+impl NpIndex {
+    /// Load an index from a nanopub typed `npx:NanopubIndex`
+    pub fn new(np: Nanopub) -> Result<Self, NpError> {
+        if !np
+            .info
+            .types
+            .iter()
+            .any(|t| t == npx::NANOPUB_INDEX.as_str())
+        {
+            return Err(NpError(format!(
+                "{} is not a nanopub index",
+                np.info.uri.as_str()
+            )));
+        }
+        let uri_subject = NamedOrBlankNodeRef::from(np.info.uri.as_ref());
+        let ns_subject = NamedOrBlankNodeRef::from(np.info.ns.as_iri_ref());
+        let objects = |predicate: NamedNodeRef| -> Vec<String> {
+            let mut objects: Vec<String> = np
+                .assertion()
+                .triples_for_predicate(predicate)
+                .filter(|t| t.subject == uri_subject || t.subject == ns_subject)
+                .filter_map(|t| match t.object {
+                    TermRef::NamedNode(iri) => Some(iri.as_str().to_string()),
+                    _ => None,
+                })
+                .collect();
+            objects.sort();
+            objects.dedup();
+            objects
+        };
+        let elements = objects(npx::INCLUDES_ELEMENT);
+        let sub_indexes = objects(npx::HAS_SUB_INDEX);
+        let appends = objects(npx::APPENDS_INDEX).into_iter().next();
+        let title = [dct::TITLE, DC_TITLE].into_iter().find_map(|predicate| {
+            np.pubinfo()
+                .triples_for_predicate(predicate)
+                .filter(|t| t.subject == uri_subject || t.subject == ns_subject)
+                .find_map(|t| match t.object {
+                    TermRef::Literal(lit) => Some(lit.value().to_string()),
+                    _ => None,
+                })
+        });
+        let complete = !np
+            .info
+            .types
+            .iter()
+            .any(|t| t == npx::INCOMPLETE_INDEX.as_str());
+        Ok(Self {
+            np,
+            elements,
+            sub_indexes,
+            appends,
+            complete,
+            title,
+        })
+    }
+
+    /// Fetch an index given its URI, using the cache when provided
+    pub async fn fetch(uri: &str, cache: Option<&dyn NpCache>) -> Result<Self, NpError> {
+        Self::new(fetch_nanopub(uri, cache).await?)
+    }
+
+    /// Get the URIs of all the elements of the index, following appended indexes and sub-indexes.
+    ///
+    /// Elements of appended indexes come first, then the elements of the sub-indexes, then the elements of this index.
+    /// The appended indexes and sub-indexes are fetched from the network when they are not in the cache, or when no cache is provided.
+    pub async fn element_uris(&self, cache: Option<&dyn NpCache>) -> Result<Vec<String>, NpError> {
+        enum Entry {
+            Index(String),
+            Element(String),
+        }
+        let mut uris: Vec<String> = Vec::new();
+        let mut seen_indexes: HashSet<String> =
+            HashSet::from([self.np.info.uri.as_str().to_string()]);
+        let mut seen_elements: HashSet<String> = HashSet::new();
+        let mut stack: Vec<Entry> = Vec::new();
+        let mut current = Some(self.clone());
+        loop {
+            if let Some(index) = current.take() {
+                // Entries are popped in reverse order from the stack
+                stack.extend(index.elements.into_iter().rev().map(Entry::Element));
+                stack.extend(index.sub_indexes.into_iter().rev().map(Entry::Index));
+                if let Some(appends) = index.appends {
+                    stack.push(Entry::Index(appends));
+                }
+            }
+            match stack.pop() {
+                Some(Entry::Element(uri)) => {
+                    if seen_elements.insert(uri.clone()) {
+                        uris.push(uri);
+                    }
+                }
+                Some(Entry::Index(uri)) => {
+                    if seen_indexes.insert(uri.clone()) {
+                        np_log!(debug, "Fetching index {uri}");
+                        current = Some(Self::fetch(&uri, cache).await?);
+                    }
+                }
+                None => break,
+            }
+        }
+        Ok(uris)
+    }
+
+    /// Fetch and check all the nanopubs of the index, following appended indexes and sub-indexes
+    pub async fn fetch_elements(
+        &self,
+        cache: Option<&dyn NpCache>,
+    ) -> Result<Vec<Nanopub>, NpError> {
+        let mut nanopubs = Vec::new();
+        for uri in self.element_uris(cache).await? {
+            let np = fetch_nanopub(&uri, cache)
+                .await
+                .and_then(Nanopub::check)
+                .map_err(|e| NpError(format!("Invalid element {uri} of the index: {e}")))?;
+            nanopubs.push(np);
+        }
+        Ok(nanopubs)
+    }
+}
+
+/// Fetch a nanopub through the cache when provided, from the network otherwise
+async fn fetch_nanopub(uri: &str, cache: Option<&dyn NpCache>) -> Result<Nanopub, NpError> {
+    match cache {
+        Some(cache) => Nanopub::fetch_cached(uri, cache).await,
+        None => Nanopub::fetch(uri).await,
+    }
+}
+
+fn parse_uri(uri: &str) -> Result<NamedNode, NpError> {
+    NamedNode::new(uri).map_err(|e| NpError(format!("Invalid IRI {uri}: {e}")))
+}
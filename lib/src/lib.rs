@@ -9,16 +9,25 @@
 // https://doc.rust-lang.org/rustdoc/write-documentation/the-doc-attribute.html
 // Can't publish because outside of pkg: #![doc = include_str!("../../docs/introduction.md")]
 
+#[macro_use]
+mod macros;
+
+pub mod builder;
+pub mod cache;
 pub mod constants;
+pub mod diff;
 pub mod error;
 pub mod extract;
+pub mod index;
 pub mod nanopub;
 pub mod network;
 pub mod profile;
+pub mod servers;
 pub mod sign;
+pub mod store;
+pub mod template;
 pub mod utils;
 pub mod vocab;
-pub use error::NpError;
 /// A module to sign, publish, or check [Nanopublications](https://nanopub.net).
 ///
 /// ## Usage
@@ -42,6 +51,10 @@ pub use error::NpError;
 /// });
 /// println!("{}", published_np)
 /// ```
-pub use nanopub::{create_base_dataset, Nanopub};
+pub use error::NpError;
+pub use nanopub::{create_base_dataset, CheckResult, Nanopub, SignOptions};
 pub use profile::{NpProfile, ProfileBuilder};
+pub use store::NanopubStore;
 pub use utils::get_np_server;
+
+pub use builder::NanopubBuilder;
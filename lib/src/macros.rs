@@ -0,0 +1,13 @@
+/// Log a message with the given level of the [`log`](https://docs.rs/log) crate when the `log` feature is enabled
+#[cfg(feature = "log")]
+macro_rules! np_log {
+    ($level:ident, $($arg:tt)+) => {
+        log::$level!($($arg)+)
+    };
+}
+
+/// Log a message when the `log` feature is enabled, compiles to nothing otherwise
+#[cfg(not(feature = "log"))]
+macro_rules! np_log {
+    ($level:ident, $($arg:tt)+) => {{}};
+}
@@ -1,21 +1,28 @@
-use crate::constants::{BOLD, END, NP_TEMP_URI, TEST_SERVER};
+use crate::builder::NanopubBuilder;
+use crate::cache::NpCache;
+use crate::constants::{NP_TEMP_URI, TEST_SERVER};
 use crate::error::NpError;
 use crate::extract::{extract_np_info, NpInfo};
-use crate::network::{fetch_np, publish_np};
+use crate::network::{fetch_np_as, publish_np, resolve_np_urls};
 use crate::profile::NpProfile;
 use crate::sign::{make_trusty, normalize_dataset, replace_bnodes, replace_ns_in_quads};
-use crate::utils::{parse_rdf, serialize_rdf, Namespace};
+use crate::utils::{
+    extract_artifact_code, parse_rdf, parse_rdf_as, serialize_rdf, serialize_rdf_as, Namespace,
+    RdfFormat,
+};
 use crate::vocab::{dct, foaf, np, npx, pav, prov};
 
 use base64::{engine, Engine as _};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use oxrdf::dataset::GraphView;
 use oxrdf::{
     vocab::{rdf, xsd},
-    Dataset, GraphNameRef, LiteralRef, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, QuadRef,
-    TripleRef,
+    Dataset, Graph, GraphNameRef, LiteralRef, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, Quad,
+    QuadRef, TermRef, TripleRef,
 };
 use rsa::pkcs8::DecodePublicKey;
 use rsa::{sha2::Digest, sha2::Sha256, Pkcs1v15Sign, RsaPublicKey};
+use serde::Serialize;
 use std::collections::HashSet;
 use std::fmt;
 
@@ -39,6 +46,82 @@ impl RdfSource for &String {
     }
 }
 
+/// Options to control the triples added to the pubinfo when signing a nanopub with [`Nanopub::sign_with`]
+#[derive(Clone, Debug)]
+pub struct SignOptions {
+    /// Add the creation date (`dct:created`) when not already set, defaults to true
+    pub add_created: bool,
+    /// Creation date to add, defaults to the date of signing
+    pub created: Option<DateTime<Utc>>,
+    /// Predicate used to add the ORCID of the profile as creator when no creator is set,
+    /// defaults to `dct:creator`, `None` to not add a creator
+    pub creator_predicate: Option<NamedNode>,
+    /// Unsign and re-sign a nanopub that is already signed, defaults to true, otherwise signing fails
+    pub allow_resign: bool,
+    /// Local name of the signature IRI in the nanopub namespace, defaults to `sig`
+    pub signature_name: String,
+    /// Namespace of the trusty URI of the signed nanopub, e.g. `https://example.org/np/`,
+    /// defaults to the namespace of the nanopub, or `https://w3id.org/np/` for temporary nanopubs
+    pub target_namespace: Option<String>,
+}
+
+impl Default for SignOptions {
+    fn default() -> Self {
+        Self {
+            add_created: true,
+            created: None,
+            creator_predicate: Some(dct::CREATOR.into_owned()),
+            allow_resign: true,
+            signature_name: "sig".to_string(),
+            target_namespace: None,
+        }
+    }
+}
+
+/// Result of the check of a nanopub trusty hash and signature, returned by [`Nanopub::verify`]
+#[derive(Clone, Serialize, Debug)]
+pub struct CheckResult {
+    pub uri: String,
+    /// False if the trusty hash or the signature does not match
+    pub valid: bool,
+    /// The nanopub has a trusty URI, and its hash matches
+    pub trusty: bool,
+    /// The nanopub is signed, and its signature matches
+    pub signed: bool,
+    pub algorithm: String,
+    pub public_key: String,
+    /// Why the nanopub is not valid
+    pub error: Option<String>,
+}
+
+impl fmt::Display for CheckResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(error) = &self.error {
+            return write!(f, "{error}");
+        }
+        let trusty = if self.trusty {
+            "trusty"
+        } else {
+            "valid (not trusty)"
+        };
+        let signed = if self.signed {
+            "with signature"
+        } else {
+            "without signature"
+        };
+        write!(f, "1 {trusty} {signed}")
+    }
+}
+
+/// The graphs of a nanopub
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NpPart {
+    Head,
+    Assertion,
+    Provenance,
+    PubInfo,
+}
+
 /// A Nanopublication, contains the nanopub info (graphs URIs, signature, etc), and the RDF dataset.
 #[derive(Clone, Debug)]
 pub struct Nanopub {
@@ -70,11 +153,13 @@ impl Nanopub {
         })
     }
 
-    /// Fetch a Nanopub given its URI.
+    /// Fetch a Nanopub given its URI, or its artifact code.
+    ///
+    /// If the URI ends with a trusty artifact code, each known nanopub server is tried until one returns a valid nanopub with this artifact code.
     ///
     /// # Arguments
     ///
-    /// * `uri` - The URI of the nanopub to fetch
+    /// * `uri` - The URI or artifact code of the nanopub to fetch
     ///
     /// # Examples
     ///
@@ -90,8 +175,83 @@ impl Nanopub {
     /// }).unwrap();
     /// ```
     pub async fn fetch(url: &str) -> Result<Self, NpError> {
-        let np_rdf = fetch_np(url).await?;
-        let (dataset, prefixes) = parse_rdf(&np_rdf)?;
+        Self::fetch_as(url, RdfFormat::TriG).await
+    }
+
+    /// Fetch a Nanopub given its URI, requesting a specific RDF format.
+    ///
+    /// The response is parsed using its `Content-Type`, the format is guessed from the content if the server does not return a RDF content type.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI or artifact code of the nanopub to fetch
+    /// * `format` - The RDF format to request
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanopub::{utils::RdfFormat, Nanopub};
+    /// use tokio::runtime;
+    /// let url = "https://w3id.org/np/RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw";
+    /// let rt = runtime::Runtime::new().expect("Runtime failed");
+    ///
+    /// let np = rt.block_on(async {
+    ///   Nanopub::fetch_as(&url, RdfFormat::NQuads).await
+    /// });
+    /// ```
+    pub async fn fetch_as(uri: &str, format: RdfFormat) -> Result<Self, NpError> {
+        Self::fetch_resolved(uri, format, true).await
+    }
+
+    /// Fetch a Nanopub given its URI or artifact code, without checking its trusty hash and signature, e.g. to inspect an invalid nanopub
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI or artifact code of the nanopub to fetch
+    pub async fn fetch_unchecked(uri: &str) -> Result<Self, NpError> {
+        Self::fetch_resolved(uri, RdfFormat::TriG, false).await
+    }
+
+    /// Fetch a Nanopub from the URLs where it can be found, until one returns the nanopub with the requested artifact code
+    async fn fetch_resolved(uri: &str, format: RdfFormat, check: bool) -> Result<Self, NpError> {
+        let Some(code) = extract_artifact_code(uri) else {
+            return Self::fetch_url(uri, format).await;
+        };
+        let mut errors: Vec<String> = Vec::new();
+        for url in resolve_np_urls(uri) {
+            // Only accept a nanopub with the requested artifact code, and valid when checked
+            match Self::fetch_url(&url, format).await.and_then(|np| {
+                if np.info.trusty_hash != code {
+                    return Err(NpError(format!(
+                        "Got nanopub {} instead of {code}",
+                        np.info.uri.as_str()
+                    )));
+                }
+                if check {
+                    np.check()
+                } else {
+                    Ok(np)
+                }
+            }) {
+                Ok(np) => return Ok(np),
+                Err(e) => errors.push(format!("{url}: {e}")),
+            }
+        }
+        let valid = if check { "valid " } else { "" };
+        Err(NpError(format!(
+            "Could not fetch a {valid}nanopub for {code}:\n{}",
+            errors.join("\n")
+        )))
+    }
+
+    /// Fetch a Nanopub from a given URL, without verifying it
+    async fn fetch_url(url: &str, format: RdfFormat) -> Result<Self, NpError> {
+        let (np_rdf, res_format) = fetch_np_as(url, format).await?;
+        let (dataset, prefixes) = if let Some(res_format) = res_format {
+            parse_rdf_as(&np_rdf, res_format)?
+        } else {
+            parse_rdf(&np_rdf)?
+        };
         let mut np_info = extract_np_info(&dataset, prefixes)?;
         np_info.published = Some(url.to_string());
         Ok(Self {
@@ -99,6 +259,41 @@ impl Nanopub {
             dataset,
         })
     }
+
+    /// Fetch a Nanopub given its URI, using a cache to avoid fetching the same trusty nanopub twice.
+    ///
+    /// Only trusty nanopubs that passed the verification are added to the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI of the nanopub to fetch
+    /// * `cache` - The cache to use, e.g. a [`MemoryCache`](crate::cache::MemoryCache) or a [`NanopubStore`](crate::NanopubStore)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nanopub::{cache::MemoryCache, Nanopub};
+    /// use tokio::runtime;
+    /// let url = "https://w3id.org/np/RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw";
+    /// let cache = MemoryCache::new(100);
+    /// let rt = runtime::Runtime::new().expect("Runtime failed");
+    ///
+    /// let np = rt.block_on(async {
+    ///   Nanopub::fetch_cached(&url, &cache).await
+    /// });
+    /// ```
+    pub async fn fetch_cached(uri: &str, cache: &dyn NpCache) -> Result<Self, NpError> {
+        if let Some(mut np) = cache.get(uri)? {
+            np.info.published = Some(uri.to_string());
+            return Ok(np);
+        }
+        let np = Self::fetch(uri).await?;
+        if !np.info.trusty_hash.is_empty() {
+            cache.put(&np)?;
+        }
+        Ok(np)
+    }
+
     /// Check a given Nanopub RDF is valid (check trusty hash and signature).
     ///
     /// A failed check will throw an error
@@ -119,12 +314,42 @@ impl Nanopub {
     ///     .build().unwrap();
     /// let np = Nanopub::new(&np_rdf).unwrap().check();
     /// ```
-    pub fn check(mut self) -> Result<Self, NpError> {
+    pub fn check(self) -> Result<Self, NpError> {
+        let result = self.verify()?;
+        if let Some(error) = result.error {
+            return Err(NpError(error));
+        }
+        np_log!(info, "Nanopub {} is valid: {result}", result.uri);
+        // Should we check if the np has been published with Nanopub::fetch?
+        Ok(self)
+    }
+
+    /// Verify the trusty hash and signature of a Nanopub, and return the result of the check.
+    ///
+    /// A wrong hash or signature is reported in the result, while a malformed nanopub throws an error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs;
+    /// use nanopub::Nanopub;
+    ///
+    /// let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig").unwrap();
+    /// let result = Nanopub::new(&np_rdf).unwrap().verify().unwrap();
+    /// assert!(result.valid && result.trusty && result.signed);
+    /// ```
+    pub fn verify(&self) -> Result<CheckResult, NpError> {
         let _ = self.is_valid()?;
-        let mut msg: String = "".to_string();
-        if self.info.trusty_hash.is_empty() {
-            msg = format!("{msg}1 valid (not trusty)");
-        } else {
+        let mut result = CheckResult {
+            uri: self.info.uri.as_str().to_string(),
+            valid: true,
+            trusty: false,
+            signed: false,
+            algorithm: self.info.algo.clone(),
+            public_key: self.info.public_key.clone(),
+            error: None,
+        };
+        if !self.info.trusty_hash.is_empty() {
             // Check Trusty hash if found
             let expected_hash = make_trusty(
                 &self.dataset,
@@ -133,15 +358,23 @@ impl Nanopub {
                 &self.info.separator_after_trusty,
             )?;
             if expected_hash != self.info.trusty_hash {
-                return Err(NpError(format!("Invalid Nanopub: the hash of the nanopublication is different than the expected hash \n{}\n{}", self.info.trusty_hash, expected_hash).to_string()));
+                np_log!(
+                    debug,
+                    "Expected trusty hash {expected_hash} for {}",
+                    result.uri
+                );
+                result.valid = false;
+                result.error = Some(format!("Invalid Nanopub: the hash of the nanopublication is different than the expected hash \n{}\n{}", self.info.trusty_hash, expected_hash));
+                return Ok(result);
             }
-            msg = format!("{msg}1 trusty");
+            result.trusty = true;
         }
 
         // Check the signature is valid if found
         if !self.info.signature.is_empty() {
             // Remove the signature from the graph before re-generating it
-            self.dataset.remove(QuadRef::new(
+            let mut dataset = self.dataset.clone();
+            dataset.remove(QuadRef::new(
                 NamedOrBlankNodeRef::from(self.info.signature_iri.as_ref()),
                 npx::HAS_SIGNATURE,
                 LiteralRef::new_simple_literal(self.info.signature.as_str()),
@@ -149,38 +382,29 @@ impl Nanopub {
             ));
             // Normalize nanopub nquads to a string
             let norm_quads = normalize_dataset(
-                &self.dataset,
+                &dataset,
                 self.info.uri.as_str(),
                 &self.info.normalized_ns,
                 &self.info.separator_after_trusty,
             )?;
-            // println!("NORMED QUADS CHECK\n{}", norm_quads);
+            np_log!(trace, "Normalized quads checked:\n{norm_quads}");
 
             // Load public key, and regenerate and check the signature hash
-            RsaPublicKey::from_public_key_der(
+            if let Err(e) = RsaPublicKey::from_public_key_der(
                 &engine::general_purpose::STANDARD.decode(&self.info.public_key)?,
             )?
             .verify(
                 Pkcs1v15Sign::new::<Sha256>(),
                 &Sha256::digest(norm_quads.as_bytes()),
                 &engine::general_purpose::STANDARD.decode(self.info.signature.as_bytes())?,
-            )?;
-            msg = format!("{msg} with signature");
-        } else {
-            msg = format!("{msg} without signature");
+            ) {
+                result.valid = false;
+                result.error = Some(NpError::from(e).to_string());
+                return Ok(result);
+            }
+            result.signed = true;
         }
-
-        println!(
-            "\n✅ Nanopub {}{}{} is valid: {}",
-            BOLD,
-            self.info.uri.as_str(),
-            END,
-            msg
-        );
-        // let rdf = serialize_rdf(&self.dataset, &self.info.uri.as_ref(), &self.info.ns.as_ref())?;
-        // TODO: should check return a string or a Nanopub? A string is not easy to process by machines
-        // Should we check if the np has been published with Nanopub::fetch?
-        Ok(self)
+        Ok(result)
     }
 
     /// Sign a nanopub: generate and add signature and trusty URI. If the nanopub is already signed, unsign it first.
@@ -202,7 +426,61 @@ impl Nanopub {
     ///     .build().unwrap();
     /// let np = Nanopub::new(&np_rdf).unwrap().sign(&profile);
     /// ```
-    pub fn sign(mut self, profile: &NpProfile) -> Result<Self, NpError> {
+    pub fn sign(self, profile: &NpProfile) -> Result<Self, NpError> {
+        self.sign_with(profile, &SignOptions::default())
+    }
+
+    /// Sign a nanopub, using the given clock to get the creation date added to the pubinfo when not already set.
+    ///
+    /// Use a fixed timestamp to make signing reproducible: signing the same input twice produces the same nanopub.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The NpProfile with private key and ORCID
+    /// * `clock` - A function returning the current date
+    pub fn sign_with_clock<C>(self, profile: &NpProfile, clock: C) -> Result<Self, NpError>
+    where
+        C: Fn() -> DateTime<Utc>,
+    {
+        let options = SignOptions {
+            created: Some(clock()),
+            ..SignOptions::default()
+        };
+        self.sign_with(profile, &options)
+    }
+
+    /// Sign a nanopub, with options to control which triples are added to the pubinfo
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The NpProfile with private key and ORCID
+    /// * `options` - The SignOptions to use, see [`SignOptions`] for the defaults used by [`Nanopub::sign`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs;
+    /// use nanopub::{Nanopub, ProfileBuilder, SignOptions};
+    /// use nanopub::vocab::pav;
+    ///
+    /// let private_key = "MIIEvQIBADANBgkqhkiG9w0BAQEFAASCBKcwggSjAgEAAoIBAQCjY1gsFxmak6SOCouJPuEzHNForkqFhgfHE3aAIAx+Y5q6UDEDM9Q0EksheNffJB4iPqsAfiFpY0ARQY92K5r8P4+a78eu9reYrb2WxZb1qPJmvR7XZ6sN1oHD7dd/EyQoJmQsmOKdrqaLRbzR7tZrf52yvKkwNWXcIVhW8uxe7iUgxiojZpW9srKoK/qFRpaUZSKn7Z/zgtDH9FJkYbBsGPDMqp78Kzt+sJb+U2W+wCSSy34jIUxx6QRbzvn6uexc/emFw/1DU5y7zBudhgC7mVk8vX1gUNKyjZBzlOmRcretrANgffqs5fx/TMHN1xtkA/H1u1IKBfKoyk/xThMLAgMBAAECggEAECuG0GZA3HF8OaqFgMG+W+agOvH04h4Pqv4cHjYNxnxpFcNV9nEssTKWSOvCwYy7hrwZBGV3PQzbjFmmrxVFs20+8yCD7KbyKKQZPVC0zf84bj6NTNgvr6DpGtDxINxuGaMjCt7enqhoRyRRuZ0fj2gD3Wqae/Ds8cpDCefkyMg0TvauHSUj244vGq5nt93txUv1Sa+/8tWZ77Dm0s5a3wUYB2IeAMl5WrO2GMvgzwH+zT+4kvNWg5S0Ze4KE+dG3lSIYZjo99h14LcQS9eALC/VBcAJ6pRXaCTT/TULtcLNeOpoc9Fu25f0yTsDt6Ga5ApliYkb7rDhV+OFrw1sYQKBgQDCE9so+dPg7qbp0cV+lbb7rrV43m5s9Klq0riS7u8m71oTwhmvm6gSLfjzqb8GLrmflCK4lKPDSTdwyvd+2SSmOXySw94zr1Pvc7sHdmMRyA7mH3m+zSOOgyCTTKyhDRCNcRIkysoL+DecDhNo4Fumf71tsqDYogfxpAQhn0re8wKBgQDXhMmmT2oXiMnYHhi2k7CJe3HUqkZgmW4W44SWqKHp0V6sjcHm0N0RT5Hz1BFFUd5Y0ZB3JLcah19myD1kKYCj7xz6oVLb8O7LeAZNlb0FsrtD7NU+Hciywo8qESiA7UYDkU6+hsmxaI01DsttMIdG4lSBbEjA7t4IQC5lyr7xiQKBgQCN87YGJ40Y5ZXCSgOZDepz9hqX2KGOIfnUv2HvXsIfiUwqTXs6HbD18xg3KL4myIBOvywSM+4ABYp+foY+Cpcq2btLIeZhiWjsKIrw71+Q/vIe0YDb1PGf6DsoYhmWBpdHzR9HN+hGjvwlsYny2L9Qbfhgxxmsuf7zeFLpQLijjwKBgH7TD28k8IOk5VKec2CNjKd600OYaA3UfCpP/OhDl/RmVtYoHWDcrBrRvkvEEd2/DZ8qw165Zl7gJs3vK+FTYvYVcfIzGPWA1KU7nkntwewmf3i7V8lT8ZTwVRsmObWU60ySJ8qKuwoBQodki2VX12NpMN1wgWe3qUUlr6gLJU4xAoGAet6nD3QKwk6TTmcGVfSWOzvpaDEzGkXjCLaxLKh9GreM/OE+h5aN2gUoFeQapG5rUwI/7Qq0xiLbRXw+OmfAoV2XKv7iI8DjdIh0F06mlEAwQ/B0CpbqkuuxphIbchtdcz/5ra233r3BMNIqBl3VDDVoJlgHPg9msOTRy13lFqc=";
+    /// let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig").unwrap();
+    /// let profile = ProfileBuilder::new(private_key.to_string())
+    ///     .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+    ///     .build().unwrap();
+    /// let options = SignOptions {
+    ///     add_created: false,
+    ///     creator_predicate: Some(pav::CREATED_BY.into_owned()),
+    ///     target_namespace: Some("https://example.org/np/".to_string()),
+    ///     ..SignOptions::default()
+    /// };
+    /// let np = Nanopub::new(&np_rdf).unwrap().sign_with(&profile, &options).unwrap();
+    /// ```
+    pub fn sign_with(
+        mut self,
+        profile: &NpProfile,
+        options: &SignOptions,
+    ) -> Result<Self, NpError> {
         // openssl_probe::init_ssl_cert_env_vars();
         // unsafe {
         //     openssl_probe::init_openssl_env_vars();
@@ -211,12 +489,30 @@ impl Nanopub {
             replace_bnodes(&self.dataset, self.info.ns.as_str(), self.info.uri.as_str())?;
         self.info = extract_np_info(&self.dataset, self.info.prefixes)?;
         if !self.info.signature.is_empty() {
-            println!("Nanopub already signed, unsigning it before re-signing");
+            if !options.allow_resign {
+                return Err(NpError(format!(
+                    "Nanopub {} is already signed, re-signing is not allowed",
+                    self.info.uri.as_str()
+                )));
+            }
+            np_log!(
+                info,
+                "Nanopub {} already signed, unsigning it before re-signing",
+                self.info.uri.as_str()
+            );
             self = self.unsign()?;
             // println!("DEBUG: Unsigned: {}", self.rdf()?);
         }
+        if let Some(target_ns) = &options.target_namespace {
+            if !target_ns.ends_with(['#', '/', '.']) {
+                return Err(NpError(format!(
+                    "Invalid target namespace {target_ns}: it should end with /, # or ."
+                )));
+            }
+            self.info.normalized_ns = target_ns.clone();
+        }
 
-        let sig_iri = self.info.ns.get("sig");
+        let sig_iri = self.info.ns.get(&options.signature_name);
         let sig_node = sig_iri.as_ref();
         let ns_node = self.info.ns.as_iri_ref();
         let uri_subject_term = NamedOrBlankNodeRef::from(self.info.uri.as_ref());
@@ -238,15 +534,18 @@ impl Nanopub {
         pubinfo_graph.insert(TripleRef::new(sig_node, npx::HAS_SIGNATURE_TARGET, ns_node));
 
         // If not already set, automatically add the current date to pubinfo created
-        if !pubinfo_graph
-            .triples_for_predicate(dct::CREATED)
-            .any(|x| x.subject == uri_subject_term || x.subject == ns_subject_term)
+        if options.add_created
+            && !pubinfo_graph
+                .triples_for_predicate(dct::CREATED)
+                .any(|x| x.subject == uri_subject_term || x.subject == ns_subject_term)
         {
             pubinfo_graph.insert(TripleRef::new(
                 ns_node,
                 dct::CREATED,
                 LiteralRef::new_typed_literal(
-                    Utc::now()
+                    options
+                        .created
+                        .unwrap_or_else(Utc::now)
                         .format("%Y-%m-%dT%H:%M:%S%.3fZ")
                         .to_string()
                         .as_str(),
@@ -256,15 +555,16 @@ impl Nanopub {
         }
 
         // If ORCID provided in profile, and not already defined in nanopub, add to pubinfo graph
-        if let Some(orcid) = &profile.orcid_id {
+        if let (Some(orcid), Some(creator_pred)) = (&profile.orcid_id, &options.creator_predicate) {
             if !pubinfo_graph.iter().any(|x| {
                 (x.subject == uri_subject_term || x.subject == ns_subject_term)
                     && (x.predicate == dct::CREATOR
                         || x.predicate == prov::WAS_ATTRIBUTED_TO
-                        || x.predicate == pav::CREATED_BY)
+                        || x.predicate == pav::CREATED_BY
+                        || x.predicate == creator_pred.as_ref())
             }) {
                 let orcid_node = NamedNodeRef::new_unchecked(orcid.as_str());
-                pubinfo_graph.insert(TripleRef::new(ns_node, dct::CREATOR, orcid_node));
+                pubinfo_graph.insert(TripleRef::new(ns_node, creator_pred.as_ref(), orcid_node));
             }
         }
 
@@ -374,10 +674,7 @@ impl Nanopub {
             } else {
                 self.info.published = Some(self.info.uri.to_string());
             }
-            // println!(
-            //     "\n🎉 Nanopublication published at {}{:?}{}",
-            //     BOLD, self.info.published, END
-            // );
+            np_log!(info, "Nanopub published at {:?}", self.info.published);
         } else {
             return Err(NpError(format!(
                 "Issue publishing the Nanopublication \n{self}"
@@ -514,8 +811,316 @@ impl Nanopub {
         })
     }
 
+    /// Create a Nanopub retracting the nanopub with the given URI, with the assertion `<creator> npx:retracts <target>`.
+    ///
+    /// The retraction should be signed with the same key as the retracted nanopub.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_uri` - The URI of the nanopub to retract
+    /// * `profile` - The NpProfile with private key and ORCID
+    pub fn new_retraction(target_uri: &str, profile: &NpProfile) -> Result<Self, NpError> {
+        let orcid = profile
+            .orcid_id
+            .as_ref()
+            .ok_or_else(|| NpError("Invalid Profile: ORCID is empty.".to_string()))?;
+        let orcid_node = NamedNode::new(orcid)
+            .map_err(|e| NpError(format!("Invalid Profile: invalid ORCID {orcid}: {e}")))?;
+        let target_node = NamedNode::new(target_uri).map_err(|e| {
+            NpError(format!(
+                "Invalid URI of the nanopub to retract {target_uri}: {e}"
+            ))
+        })?;
+        NanopubBuilder::new()
+            .add_assertion(orcid_node.clone(), npx::RETRACTS, target_node)
+            .add_provenance(
+                NanopubBuilder::local("assertion"),
+                prov::WAS_ATTRIBUTED_TO,
+                orcid_node,
+            )
+            .with_creator(orcid)
+            .build()
+    }
+
+    /// Check this nanopub retracts the given target nanopub, and is signed with the same public key
+    pub fn check_retraction(&self, target: &Nanopub) -> Result<(), NpError> {
+        if !self
+            .assertion()
+            .triples_for_predicate(npx::RETRACTS)
+            .any(|t| t.object == TermRef::from(target.info.uri.as_ref()))
+        {
+            return Err(NpError(format!(
+                "Invalid retraction: {} does not retract {}",
+                self.info.uri, target.info.uri
+            )));
+        }
+        if self.info.public_key.is_empty() {
+            return Err(NpError(
+                "Invalid retraction: the retraction is not signed".to_string(),
+            ));
+        }
+        if self.info.public_key != target.info.public_key {
+            return Err(NpError(format!(
+                "Invalid retraction: it is not signed with the same public key as {}",
+                target.info.uri
+            )));
+        }
+        Ok(())
+    }
+
+    /// Create a new version of this signed nanopub, superseding it with `npx:supersedes`.
+    ///
+    /// The assertion can be edited with the given closure, then the new version is signed with the profile,
+    /// which must have the same public key as this nanopub. It keeps the namespace of this nanopub trusty URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The NpProfile used to sign this nanopub
+    /// * `edit_assertion` - A closure editing the triples of the assertion graph
+    pub fn supersede<F>(&self, profile: &NpProfile, edit_assertion: F) -> Result<Self, NpError>
+    where
+        F: FnOnce(&mut Graph),
+    {
+        if self.info.public_key.is_empty() {
+            return Err(NpError(format!(
+                "Cannot supersede {}: it is not signed",
+                self.info.uri
+            )));
+        }
+        if self.info.public_key != profile.public_key {
+            return Err(NpError(format!(
+                "Cannot supersede {}: it was signed with a different key than the one in the profile",
+                self.info.uri
+            )));
+        }
+        let old_uri = self.info.uri.clone();
+        let options = SignOptions {
+            target_namespace: (!self.info.trusty_hash.is_empty())
+                .then(|| self.info.normalized_ns.clone()),
+            ..SignOptions::default()
+        };
+        let mut np = self.clone().unsign()?;
+
+        // Edit the assertion graph
+        let assertion_graph = GraphNameRef::from(np.info.assertion.as_ref());
+        let assertion_quads: Vec<Quad> = np
+            .dataset
+            .quads_for_graph_name(assertion_graph)
+            .map(QuadRef::into_owned)
+            .collect();
+        let mut assertion = Graph::new();
+        for quad in &assertion_quads {
+            np.dataset.remove(quad);
+            assertion.insert(TripleRef::new(&quad.subject, &quad.predicate, &quad.object));
+        }
+        edit_assertion(&mut assertion);
+        for triple in assertion.iter() {
+            np.dataset.insert(triple.in_graph(assertion_graph));
+        }
+
+        // Replace the creation date and the superseded nanopub in the pubinfo
+        let pubinfo_graph = GraphNameRef::from(np.info.pubinfo.as_ref());
+        let uri_subject = NamedOrBlankNodeRef::from(np.info.uri.as_ref());
+        let ns_subject = NamedOrBlankNodeRef::from(np.info.ns.as_iri_ref());
+        let outdated_quads: Vec<Quad> = np
+            .dataset
+            .quads_for_graph_name(pubinfo_graph)
+            .filter(|q| {
+                (q.subject == uri_subject || q.subject == ns_subject)
+                    && (q.predicate == dct::CREATED || q.predicate == npx::SUPERSEDES)
+            })
+            .map(QuadRef::into_owned)
+            .collect();
+        for quad in &outdated_quads {
+            np.dataset.remove(quad);
+        }
+        np.dataset.insert(QuadRef::new(
+            np.info.ns.as_iri_ref(),
+            npx::SUPERSEDES,
+            old_uri.as_ref(),
+            pubinfo_graph,
+        ));
+        np.sign_with(profile, &options)
+    }
+
+    /// IRI of the given graph of the nanopub
+    pub fn graph_iri(&self, part: NpPart) -> &NamedNode {
+        match part {
+            NpPart::Head => &self.info.head,
+            NpPart::Assertion => &self.info.assertion,
+            NpPart::Provenance => &self.info.prov,
+            NpPart::PubInfo => &self.info.pubinfo,
+        }
+    }
+
+    /// View of the triples in the given graph of the nanopub
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs;
+    /// use nanopub::{nanopub::NpPart, Nanopub};
+    /// use oxrdf::Graph;
+    ///
+    /// let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig").unwrap();
+    /// let np = Nanopub::new(&np_rdf).unwrap();
+    /// assert_eq!(np.graph(NpPart::Assertion).len(), 1);
+    /// let assertion: Graph = np.assertion().iter().collect();
+    /// ```
+    pub fn graph(&self, part: NpPart) -> GraphView<'_> {
+        self.dataset.graph(self.graph_iri(part).as_ref())
+    }
+
+    /// View of the triples in the assertion graph
+    pub fn assertion(&self) -> GraphView<'_> {
+        self.graph(NpPart::Assertion)
+    }
+
+    /// View of the triples in the provenance graph
+    pub fn provenance(&self) -> GraphView<'_> {
+        self.graph(NpPart::Provenance)
+    }
+
+    /// View of the triples in the pubinfo graph
+    pub fn pubinfo(&self) -> GraphView<'_> {
+        self.graph(NpPart::PubInfo)
+    }
+
+    /// URIs of the other trusty nanopubs referenced in this nanopub, sorted.
+    ///
+    /// IRIs of resources defined in another nanopub, e.g. `https://w3id.org/np/RA...#assertion`, give the URI of that nanopub.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs;
+    /// use nanopub::Nanopub;
+    ///
+    /// let np_rdf = fs::read_to_string("./tests/testsuite/valid/signed/nanobench_hasRead-template-v5.trig").unwrap();
+    /// let np = Nanopub::new(&np_rdf).unwrap();
+    /// for uri in np.references() {
+    ///     println!("{uri}");
+    /// }
+    /// ```
+    pub fn references(&self) -> Vec<String> {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut uris: Vec<String> = Vec::new();
+        for quad in self.dataset.iter() {
+            let subject = match quad.subject {
+                NamedOrBlankNodeRef::NamedNode(iri) => Some(iri.as_str()),
+                _ => None,
+            };
+            let object = match quad.object {
+                TermRef::NamedNode(iri) => Some(iri.as_str()),
+                _ => None,
+            };
+            for iri in [subject, object].into_iter().flatten() {
+                let Some(code) = extract_artifact_code(iri) else {
+                    continue;
+                };
+                if code == self.info.trusty_hash || !seen.insert(code) {
+                    continue;
+                }
+                let end = iri.rfind(code).unwrap_or_default() + code.len();
+                uris.push(iri[..end].to_string());
+            }
+        }
+        uris.sort();
+        uris
+    }
+
+    /// Add a triple to the given graph of the nanopub, and update the nanopub info.
+    ///
+    /// Returns false if the triple was already in the graph. The nanopub needs to be signed again after changes.
+    pub fn add_triple<'a>(
+        &mut self,
+        part: NpPart,
+        triple: impl Into<TripleRef<'a>>,
+    ) -> Result<bool, NpError> {
+        let quad = triple
+            .into()
+            .in_graph(self.graph_iri(part).as_ref())
+            .into_owned();
+        if !self.dataset.insert(&quad) {
+            return Ok(false);
+        }
+        self.refresh_info().inspect_err(|_| {
+            self.dataset.remove(&quad);
+        })?;
+        Ok(true)
+    }
+
+    /// Remove a triple from the given graph of the nanopub, and update the nanopub info.
+    ///
+    /// Returns false if the triple was not in the graph. The nanopub needs to be signed again after changes.
+    pub fn remove_triple<'a>(
+        &mut self,
+        part: NpPart,
+        triple: impl Into<TripleRef<'a>>,
+    ) -> Result<bool, NpError> {
+        let quad = triple
+            .into()
+            .in_graph(self.graph_iri(part).as_ref())
+            .into_owned();
+        if !self.dataset.remove(&quad) {
+            return Ok(false);
+        }
+        self.refresh_info().inspect_err(|_| {
+            self.dataset.insert(&quad);
+        })?;
+        Ok(true)
+    }
+
+    /// Extract the nanopub info again after the dataset has been changed
+    fn refresh_info(&mut self) -> Result<(), NpError> {
+        self.info = extract_np_info(&self.dataset, self.info.prefixes.clone())?;
+        Ok(())
+    }
+
     /// Check if Nanopub is valid: minimal required triples in assertion, prov, pubinfo graphs
     pub fn is_valid(&self) -> Result<bool, NpError> {
+        match self.structure_errors().into_iter().next() {
+            Some(error) => Err(NpError(format!("Invalid Nanopub: {error}"))),
+            None => Ok(true),
+        }
+    }
+
+    /// Warnings on the nanopub, without failing like [`Nanopub::check`]: problems in its structure,
+    /// triples outside of its graphs, and missing trusty hash, signature, creator, or creation date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs;
+    /// use nanopub::Nanopub;
+    ///
+    /// let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig").unwrap();
+    /// let warnings = Nanopub::new(&np_rdf).unwrap().warnings();
+    /// assert!(warnings.contains(&"not signed.".to_string()));
+    /// ```
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = self.structure_errors();
+        if self.dataset.iter().any(|q| q.graph_name.is_default_graph()) {
+            warnings.push("some triples are not in a graph.".to_string());
+        }
+        if self.info.trusty_hash.is_empty() {
+            warnings.push("no trusty hash in the nanopub URI.".to_string());
+        }
+        if self.info.signature.is_empty() {
+            warnings.push("not signed.".to_string());
+        }
+        if self.info.creators.is_empty() {
+            warnings.push("no creator in the pubinfo graph.".to_string());
+        }
+        if self.info.created.is_none() {
+            warnings.push("no creation date in the pubinfo graph.".to_string());
+        }
+        warnings
+    }
+
+    /// Problems in the structure of the nanopub that make it invalid
+    fn structure_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
         let prov_node = self.info.prov.as_ref();
         let pubinfo_node = self.info.pubinfo.as_ref();
         if self
@@ -524,9 +1129,7 @@ impl Nanopub {
             .next()
             .is_none()
         {
-            return Err(NpError(
-                "Invalid Nanopub: no triples in the assertion graph.".to_string(),
-            ));
+            errors.push("no triples in the assertion graph.".to_string());
         }
         if self
             .dataset
@@ -534,18 +1137,18 @@ impl Nanopub {
             .next()
             .is_none()
         {
-            return Err(NpError(
-                "Invalid Nanopub: no triples in the provenance graph.".to_string(),
-            ));
-        }
-        if self
+            errors.push("no triples in the provenance graph.".to_string());
+        } else if self
             .dataset
             .graph(prov_node)
             .triples_for_subject(self.info.assertion.as_ref())
             .next()
             .is_none()
         {
-            return Err(NpError("Invalid Nanopub: no triples with the assertion graph as subject in the provenance graph.".to_string()));
+            errors.push(
+                "no triples with the assertion graph as subject in the provenance graph."
+                    .to_string(),
+            );
         }
         if self
             .dataset
@@ -553,18 +1156,14 @@ impl Nanopub {
             .next()
             .is_none()
         {
-            return Err(NpError(
-                "Invalid Nanopub: no triples in the pubinfo graph.".to_string(),
-            ));
-        }
-        if !self.dataset.quads_for_graph_name(pubinfo_node).any(|x| {
+            errors.push("no triples in the pubinfo graph.".to_string());
+        } else if !self.dataset.quads_for_graph_name(pubinfo_node).any(|x| {
             x.subject == NamedOrBlankNodeRef::from(self.info.uri.as_ref())
                 || x.subject == NamedOrBlankNodeRef::from(self.info.ns.as_iri_ref())
         }) {
-            return Err(NpError(
-                "Invalid Nanopub: no triples with the nanopub URI as subject in the pubinfo graph."
-                    .to_string(),
-            ));
+            errors.push(
+                "no triples with the nanopub URI as subject in the pubinfo graph.".to_string(),
+            );
         }
         let graph_names: HashSet<GraphNameRef> = self
             .dataset
@@ -573,11 +1172,9 @@ impl Nanopub {
             .map(|g| g.graph_name)
             .collect();
         if graph_names.len() > 4 {
-            return Err(NpError(
-                format!("Invalid Nanopub: it should have 4 graphs (head, assertion, provenance, pubinfo), but the given nanopub has {} graphs.", graph_names.len())
-            ));
+            errors.push(format!("it should have 4 graphs (head, assertion, provenance, pubinfo), but the given nanopub has {} graphs.", graph_names.len()));
         }
-        Ok(true)
+        errors
     }
 
     /// Returns the RDF of the nanopub
@@ -589,6 +1186,17 @@ impl Nanopub {
             &self.info.prefixes,
         )
     }
+
+    /// Returns the RDF of the nanopub in the given format
+    pub fn rdf_as(&self, format: RdfFormat) -> Result<String, NpError> {
+        serialize_rdf_as(
+            &self.dataset,
+            self.info.uri.as_str(),
+            self.info.ns.as_str(),
+            &self.info.prefixes,
+            format,
+        )
+    }
 }
 
 /// Bootstrap a base nanopub dataset that can be edited later
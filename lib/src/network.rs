@@ -1,4 +1,7 @@
+use crate::constants::NP_PREF_NS;
 use crate::error::NpError;
+use crate::servers::np_servers;
+use crate::utils::{extract_artifact_code, RdfFormat};
 
 /// Publish nanopub RDF string to a given nanopub server URL
 pub async fn publish_np(server: &str, np: &str) -> Result<bool, NpError> {
@@ -29,13 +32,84 @@ pub async fn publish_np(server: &str, np: &str) -> Result<bool, NpError> {
 
 /// Fetch nanopub from its URI
 pub async fn fetch_np(uri: &str) -> Result<String, NpError> {
+    Ok(fetch_np_as(uri, RdfFormat::TriG).await?.0)
+}
+
+/// Fetch nanopub from its URI, requesting the given RDF format.
+///
+/// Returns the response body, and the RDF format of the response given by its `Content-Type`
+/// (`None` when the server did not provide a content type specific to RDF, e.g. `text/plain`, then the format is guessed from the content).
+/// Fails when the response has another content type, e.g. the HTML landing page of a nanopub
+pub async fn fetch_np_as(
+    uri: &str,
+    format: RdfFormat,
+) -> Result<(String, Option<RdfFormat>), NpError> {
+    // Ask for the requested format first, but accept the other RDF formats
+    let accept = [RdfFormat::TriG, RdfFormat::NQuads, RdfFormat::JsonLd]
+        .iter()
+        .filter(|f| **f != format)
+        .fold(format.media_type().to_string(), |accept, f| {
+            format!("{accept}, {};q=0.9", f.media_type())
+        });
     let client = reqwest::Client::new();
     let res = client
         .get(uri)
-        .header(reqwest::header::ACCEPT, "application/trig")
+        .header(reqwest::header::ACCEPT, accept)
         .send()
-        .await?
-        .text()
         .await?;
-    Ok(res)
+    let status = res.status();
+    if !status.is_success() {
+        return Err(NpError(format!("Error fetching {uri}: {status}")));
+    }
+    let content_type = res
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let res_format = RdfFormat::from_media_type(&content_type);
+    if res_format.is_none() && !is_generic_media_type(&content_type) {
+        return Err(NpError(format!(
+            "Error fetching {uri}: the server did not return RDF ({status}, Content-Type: {content_type})"
+        )));
+    }
+    Ok((res.text().await?, res_format))
+}
+
+/// Check if a media type does not tell the format of the content, which is then guessed when parsing it,
+/// e.g. plain JSON can be JSON-LD, and some servers return TriG as `text/plain`
+fn is_generic_media_type(content_type: &str) -> bool {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    [
+        "",
+        "text/plain",
+        "application/octet-stream",
+        "application/json",
+    ]
+    .contains(&media_type.as_str())
+}
+
+/// Get the list of URLs where a nanopub can be fetched, given its URI or its artifact code.
+///
+/// The URI itself comes first if it is a HTTP URL, then the nanopub registries, and the w3id.org resolver.
+pub fn resolve_np_urls(uri: &str) -> Vec<String> {
+    let uri = uri.trim();
+    let mut urls: Vec<String> = Vec::new();
+    if uri.starts_with("http://") || uri.starts_with("https://") {
+        urls.push(uri.to_string());
+    }
+    if let Some(code) = extract_artifact_code(uri) {
+        for server in np_servers().iter().map(String::as_str).chain([NP_PREF_NS]) {
+            let url = format!("{server}{code}");
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    urls
 }
@@ -4,7 +4,8 @@ use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePubl
 use rsa::{RsaPrivateKey, RsaPublicKey};
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use std::io::{BufRead as _, BufReader};
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::path::{Path, PathBuf};
 use std::{env, fs};
 
 use crate::error::NpError;
@@ -92,12 +93,10 @@ impl ProfileBuilder {
     pub fn from_file(filepath: &str) -> Result<NpProfile, NpError> {
         let filepath = if filepath.is_empty() {
             // Default to home dir if nothing provided
-            format!(
-                "{}/.nanopub/profile.yml",
-                env::var("HOME")
-                    .or_else(|_| env::var("USERPROFILE"))
-                    .unwrap_or("~".to_string())
-            )
+            default_profile_dir()
+                .join("profile.yml")
+                .to_string_lossy()
+                .to_string()
         } else {
             filepath.to_string()
         };
@@ -114,12 +113,12 @@ impl ProfileBuilder {
                 line.map_err(|_| NpError("Failed to read line in profile.yml".to_string()))?;
             if let Some((key, value)) = line.split_once(": ") {
                 match key.trim() {
-                    "private_key" => privkey_path = Some(remove_quotes(value)),
-                    // "public_key" => pubkey_path = Some(remove_quotes(value)),
-                    "orcid_id" => orcid = Some(remove_quotes(value)).filter(|s| !s.is_empty()),
-                    "name" => name = Some(remove_quotes(value)).filter(|s| !s.is_empty()),
+                    "private_key" => privkey_path = Some(yaml_unquote(value)),
+                    // "public_key" => pubkey_path = Some(yaml_unquote(value)),
+                    "orcid_id" => orcid = Some(yaml_unquote(value)).filter(|s| !s.is_empty()),
+                    "name" => name = Some(yaml_unquote(value)).filter(|s| !s.is_empty()),
                     "introduction_nanopub_uri" => {
-                        intro_np_uri = Some(remove_quotes(value)).filter(|s| !s.is_empty())
+                        intro_np_uri = Some(yaml_unquote(value)).filter(|s| !s.is_empty())
                     }
                     _ => {}
                 }
@@ -159,6 +158,43 @@ impl NpProfile {
     pub fn get_public_key(&self) -> Result<RsaPublicKey, NpError> {
         Ok(RsaPublicKey::from(&self.get_private_key()?))
     }
+
+    /// Write the keys to `id_rsa` and `id_rsa.pub` in the given directory, with a `profile.yml` pointing to them.
+    ///
+    /// Existing files are overwritten. Returns the path to the `profile.yml`, which can be loaded with [`ProfileBuilder::from_file`]
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf, NpError> {
+        fs::create_dir_all(&dir)?;
+        let dir = fs::canonicalize(dir)?;
+        let privkey_path = dir.join("id_rsa");
+        let pubkey_path = dir.join("id_rsa.pub");
+        let profile_path = dir.join("profile.yml");
+        // Only the owner can read the private key, from the creation of the file
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut privkey_file = options.open(&privkey_path)?;
+        // An existing file keeps its permissions when opened
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            privkey_file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        privkey_file.write_all(self.private_key.as_bytes())?;
+        fs::write(&pubkey_path, &self.public_key)?;
+        fs::write(
+            &profile_path,
+            format!(
+                "orcid_id: {}\nname: {}\npublic_key: {}\nprivate_key: {}\nintroduction_nanopub_uri: {}\n",
+                yaml_quote(self.orcid_id.as_deref().unwrap_or_default()),
+                yaml_quote(self.name.as_deref().unwrap_or_default()),
+                yaml_quote(&pubkey_path.to_string_lossy()),
+                yaml_quote(&privkey_path.to_string_lossy()),
+                yaml_quote(self.introduction_nanopub_uri.as_deref().unwrap_or_default()),
+            ),
+        )?;
+        Ok(profile_path)
+    }
 }
 
 impl fmt::Display for NpProfile {
@@ -179,6 +215,16 @@ impl fmt::Display for NpProfile {
     }
 }
 
+/// Default directory of the profile and keys: `~/.nanopub`
+pub fn default_profile_dir() -> PathBuf {
+    Path::new(
+        &env::var("HOME")
+            .or_else(|_| env::var("USERPROFILE"))
+            .unwrap_or("~".to_string()),
+    )
+    .join(".nanopub")
+}
+
 /// Normalize a private or public key string - remove headers/footers and newlines
 pub fn normalize_key(key: &str) -> Result<String, NpError> {
     let key_trimmed = key.trim();
@@ -277,3 +323,20 @@ pub fn gen_keys() -> Result<(String, String), NpError> {
 fn remove_quotes(value: &str) -> String {
     value.trim().trim_matches(['"', '\'']).trim().to_string()
 }
+
+/// Quote a YAML value, escaping quotes, backslashes, and newlines, so it stays on one line.
+/// JSON strings are valid YAML double-quoted scalars
+fn yaml_quote(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Get a YAML value, unescaping double-quoted values written by [`yaml_quote`]
+fn yaml_unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('"') {
+        if let Ok(unquoted) = serde_json::from_str::<String>(value) {
+            return unquoted;
+        }
+    }
+    remove_quotes(value)
+}
@@ -0,0 +1,170 @@
+use crate::constants::{LIST_SERVERS, MONITOR_URL};
+use crate::error::NpError;
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::RwLock;
+
+/// Servers used by [`get_np_server`](crate::get_np_server), [`LIST_SERVERS`] is used when empty
+static NP_SERVERS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// If the servers were already loaded from the monitor by [`init_np_servers`]
+static MONITOR_LOADED: AtomicBool = AtomicBool::new(false);
+
+/// A nanopub server, with its health as reported by the nanopub monitor
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct NpServer {
+    pub url: String,
+    pub status: Option<String>,
+    /// Average response time in milliseconds
+    pub response_time: Option<f64>,
+    /// Ratio of successful requests, between 0 and 1
+    pub success_ratio: Option<f64>,
+}
+
+impl NpServer {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            status: None,
+            response_time: None,
+            success_ratio: None,
+        }
+    }
+
+    /// Check if the server is considered healthy, servers without health information are considered healthy
+    pub fn is_healthy(&self) -> bool {
+        let status_ok = self.status.as_ref().is_none_or(|status| {
+            matches!(status.to_ascii_lowercase().as_str(), "ok" | "up" | "ready")
+        });
+        status_ok && self.success_ratio.is_none_or(|ratio| ratio >= 0.9)
+    }
+}
+
+/// Load the list of nanopub servers from the JSON output of the nanopub monitor, default to [`MONITOR_URL`]
+pub async fn load_servers_from_monitor(
+    monitor_url: Option<&str>,
+) -> Result<Vec<NpServer>, NpError> {
+    let monitor_url = monitor_url.unwrap_or(MONITOR_URL);
+    let res = reqwest::Client::new()
+        .get(monitor_url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(NpError(format!(
+            "Error loading servers from {monitor_url}: {status}"
+        )));
+    }
+    parse_servers(&res.text().await?)
+}
+
+/// Load the list of nanopub servers from a file, either the JSON output of the monitor, or one URL per line
+pub fn load_servers_from_file(filepath: &str) -> Result<Vec<NpServer>, NpError> {
+    parse_servers(&fs::read_to_string(filepath)?)
+}
+
+/// A service in the JSON output of the nanopub monitor
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorEntry {
+    url: String,
+    #[serde(rename = "type")]
+    service_type: Option<String>,
+    status: Option<String>,
+    response_time: Option<f64>,
+    success_ratio: Option<f64>,
+}
+
+/// Parse a list of nanopub servers, either from the JSON output of the monitor, or one URL per line.
+///
+/// The JSON output is an array of services with their `url`, `type`, `status`, `responseTime` in milliseconds,
+/// and `successRatio`. Only the nanopub registries are kept.
+pub fn parse_servers(content: &str) -> Result<Vec<NpServer>, NpError> {
+    if !content.trim_start().starts_with('[') {
+        return Ok(content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(NpServer::new)
+            .collect());
+    }
+    let entries: Vec<MonitorEntry> = serde_json::from_str(content)
+        .map_err(|e| NpError(format!("Invalid JSON list of servers: {e}")))?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            entry
+                .service_type
+                .as_ref()
+                .is_none_or(|t| t.to_ascii_lowercase().contains("registry"))
+        })
+        .map(|entry| NpServer {
+            url: entry.url,
+            status: entry.status,
+            response_time: entry.response_time,
+            success_ratio: entry.success_ratio,
+        })
+        .collect())
+}
+
+/// Rank servers: healthy servers first, then by response time, and success ratio
+pub fn rank_servers(mut servers: Vec<NpServer>) -> Vec<NpServer> {
+    fn cmp_option(a: Option<f64>, b: Option<f64>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+    servers.sort_by(|a, b| {
+        b.is_healthy()
+            .cmp(&a.is_healthy())
+            .then_with(|| cmp_option(a.response_time, b.response_time))
+            .then_with(|| cmp_option(b.success_ratio, a.success_ratio))
+    });
+    servers
+}
+
+/// Load the servers from the nanopub monitor and set them with [`set_np_servers`], only once per process.
+///
+/// The following calls do nothing, even when the first one failed, so an unreachable monitor is only waited for once.
+pub async fn init_np_servers() -> Result<(), NpError> {
+    if MONITOR_LOADED.swap(true, atomic::Ordering::SeqCst) {
+        return Ok(());
+    }
+    set_np_servers(load_servers_from_monitor(None).await?)
+}
+
+/// Set the servers used by [`get_np_server`](crate::get_np_server), ranking them and keeping only healthy ones.
+///
+/// The default [`LIST_SERVERS`] are used again if no healthy server is provided.
+pub fn set_np_servers(servers: Vec<NpServer>) -> Result<(), NpError> {
+    let urls: Vec<String> = rank_servers(servers)
+        .into_iter()
+        .filter(NpServer::is_healthy)
+        .map(|server| {
+            if server.url.ends_with('/') {
+                server.url
+            } else {
+                format!("{}/", server.url)
+            }
+        })
+        .collect();
+    *NP_SERVERS
+        .write()
+        .map_err(|e| NpError(format!("Failed to set the list of servers: {e}")))? = urls;
+    Ok(())
+}
+
+/// Get the list of servers used, ranked from best to worst. Default to [`LIST_SERVERS`]
+pub fn np_servers() -> Vec<String> {
+    match NP_SERVERS.read() {
+        Ok(servers) if !servers.is_empty() => servers.clone(),
+        _ => LIST_SERVERS.iter().map(|s| s.to_string()).collect(),
+    }
+}
@@ -9,7 +9,7 @@ use oxrdf::{
 use regex::Regex;
 use rsa::{sha2::Digest, sha2::Sha256};
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Generate TrustyURI using base64 encoding
 pub fn make_trusty(
@@ -32,6 +32,68 @@ pub fn make_trusty(
     ))
 }
 
+/// Number the blank nodes of a dataset starting from 1, in an order that does not depend on their generated ids,
+/// so that signing the same RDF twice gives the same URIs.
+///
+/// Blank nodes are labelled with the triples they appear in, refined with the labels of their neighbours until stable.
+pub(crate) fn number_bnodes(dataset: &Dataset) -> HashMap<String, usize> {
+    let mut labels: HashMap<&str, String> = HashMap::new();
+    for quad in dataset.iter() {
+        if let NamedOrBlankNodeRef::BlankNode(bnode) = quad.subject {
+            labels.insert(bnode.as_str(), String::new());
+        }
+        if let TermRef::BlankNode(bnode) = quad.object {
+            labels.insert(bnode.as_str(), String::new());
+        }
+    }
+    let mut distinct_labels = 0;
+    for _ in 0..=labels.len() {
+        let mut contexts: HashMap<&str, Vec<String>> = HashMap::new();
+        for quad in dataset.iter() {
+            let subject = match quad.subject {
+                NamedOrBlankNodeRef::BlankNode(bnode) => format!("_:{}", labels[bnode.as_str()]),
+                subject => subject.to_string(),
+            };
+            let object = match quad.object {
+                TermRef::BlankNode(bnode) => format!("_:{}", labels[bnode.as_str()]),
+                object => object.to_string(),
+            };
+            if let NamedOrBlankNodeRef::BlankNode(bnode) = quad.subject {
+                contexts
+                    .entry(bnode.as_str())
+                    .or_default()
+                    .push(format!("s {} {object} {}", quad.predicate, quad.graph_name));
+            }
+            if let TermRef::BlankNode(bnode) = quad.object {
+                contexts.entry(bnode.as_str()).or_default().push(format!(
+                    "o {subject} {} {}",
+                    quad.predicate, quad.graph_name
+                ));
+            }
+        }
+        for (bnode, mut context) in contexts {
+            context.sort();
+            let label = format!("{}\n{}", labels[bnode], context.join("\n"));
+            labels.insert(
+                bnode,
+                engine::general_purpose::STANDARD.encode(Sha256::digest(label.as_bytes())),
+            );
+        }
+        let distinct = labels.values().collect::<HashSet<_>>().len();
+        if distinct == distinct_labels {
+            break;
+        }
+        distinct_labels = distinct;
+    }
+    let mut bnodes: Vec<(&str, String)> = labels.into_iter().collect();
+    bnodes.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+    bnodes
+        .into_iter()
+        .enumerate()
+        .map(|(i, (bnode, _))| (bnode.to_string(), i + 1))
+        .collect()
+}
+
 /// Replace bnodes by URI ending with `_1` in the RDF dataset
 pub fn replace_bnodes(
     dataset: &Dataset,
@@ -39,21 +101,14 @@ pub fn replace_bnodes(
     base_uri: &str,
 ) -> Result<Dataset, NpError> {
     let mut new_dataset = Dataset::new();
-    let mut bnode_map: HashMap<String, usize> = HashMap::new();
-    let mut bnode_count = 1;
+    let bnode_map = number_bnodes(dataset);
     let re_underscore_uri = Regex::new(&format!(r"{base_uri}.?(_+[a-zA-Z0-9^_]+)$"))?;
 
     for quad in dataset.iter() {
         // Replace bnode in subjects, and add 1 underscore for URI using already underscore
         let subject_string = match quad.subject {
             NamedOrBlankNodeRef::BlankNode(bnode) => {
-                let bnode_id = bnode.as_str();
-                bnode_map.entry(bnode_id.to_string()).or_insert_with(|| {
-                    let counter = bnode_count;
-                    bnode_count += 1;
-                    counter
-                });
-                format!("{}_{}", base_ns, bnode_map[bnode_id])
+                format!("{}_{}", base_ns, bnode_map[bnode.as_str()])
             }
             NamedOrBlankNodeRef::NamedNode(named) => {
                 let mut subject_iri_str = named.as_str().to_owned();
@@ -92,13 +147,7 @@ pub fn replace_bnodes(
         // let object = quad.object;
         match quad.object {
             TermRef::BlankNode(bnode) => {
-                let bnode_id = bnode.as_str();
-                bnode_map.entry(bnode_id.to_string()).or_insert_with(|| {
-                    let counter = bnode_count;
-                    bnode_count += 1;
-                    counter
-                });
-                let object_string = format!("{}_{}", base_ns, bnode_map[bnode_id]);
+                let object_string = format!("{}_{}", base_ns, bnode_map[bnode.as_str()]);
                 let object_node = NamedNodeRef::new_unchecked(object_string.as_str());
                 new_dataset.insert(QuadRef::new(
                     subject_node,
@@ -0,0 +1,129 @@
+use crate::error::NpError;
+use crate::nanopub::Nanopub;
+use crate::utils::extract_artifact_code;
+
+use getrandom::fill;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A local file-system store of trusty nanopubs, keyed by their artifact code.
+///
+/// Nanopubs are stored as TriG files in sub-directories named after the 2 characters following
+/// the `RA` prefix of their artifact code, e.g. `<root>/e_/RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4.trig`
+///
+/// # Examples
+///
+/// ```
+/// use std::fs;
+/// use nanopub::{Nanopub, NanopubStore};
+///
+/// let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig").unwrap();
+/// let store = NanopubStore::new(std::env::temp_dir().join("nanopub-store-doc")).unwrap();
+/// store.put(&Nanopub::new(&np_rdf).unwrap()).unwrap();
+/// assert!(store.contains("https://w3id.org/np/RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct NanopubStore {
+    root: PathBuf,
+}
+
+impl NanopubStore {
+    /// Open a store at the given directory, creating it if it does not exist
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self, NpError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Add a nanopub to the store, it is checked before being written. Returns the path of the stored file.
+    pub fn put(&self, np: &Nanopub) -> Result<PathBuf, NpError> {
+        if np.info.trusty_hash.is_empty() {
+            return Err(NpError(format!(
+                "Only trusty nanopubs can be stored, no trusty hash found in {}",
+                np.info.uri
+            )));
+        }
+        let np = np.clone().check()?;
+        let path = self.path_for(&np.info.trusty_hash);
+        if path.exists() {
+            return Ok(path);
+        }
+        let dir = path
+            .parent()
+            .ok_or_else(|| NpError(format!("Invalid store path {path:?}")))?;
+        fs::create_dir_all(dir)?;
+        // Write to a temporary file in the same directory, then rename it, so readers never see a partial file
+        let mut buf = [0u8; 4];
+        fill(&mut buf).map_err(|e| NpError(format!("Failed to generate random number: {e}")))?;
+        let tmp_path = dir.join(format!(
+            ".{}.{}.tmp",
+            np.info.trusty_hash,
+            u32::from_ne_bytes(buf)
+        ));
+        fs::write(&tmp_path, np.rdf()?)?;
+        fs::rename(&tmp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })?;
+        Ok(path)
+    }
+
+    /// Get a nanopub from the store given its URI or artifact code, `None` if not stored
+    pub fn get(&self, uri: &str) -> Result<Option<Nanopub>, NpError> {
+        let Some(code) = extract_artifact_code(uri) else {
+            return Ok(None);
+        };
+        let path = self.path_for(code);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Nanopub::new(&fs::read_to_string(path)?)?))
+    }
+
+    /// Check if a nanopub is in the store given its URI or artifact code
+    pub fn contains(&self, uri: &str) -> bool {
+        extract_artifact_code(uri).is_some_and(|code| self.path_for(code).exists())
+    }
+
+    /// Iterate over all nanopubs in the store
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<Nanopub, NpError>>, NpError> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for dir in fs::read_dir(&self.root)? {
+            let dir = dir?.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(&dir)? {
+                let file = file?.path();
+                let is_np_file = file
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("RA") && name.ends_with(".trig"));
+                if is_np_file {
+                    paths.push(file);
+                }
+            }
+        }
+        paths.sort();
+        Ok(paths
+            .into_iter()
+            .map(|path| Nanopub::new(&fs::read_to_string(path)?)))
+    }
+
+    /// Fetch a nanopub from the store if present, otherwise from the network, storing it if it is trusty.
+    ///
+    /// Trusty nanopubs are immutable, so once stored they are never fetched from the network again.
+    pub async fn fetch(&self, uri: &str) -> Result<Nanopub, NpError> {
+        Nanopub::fetch_cached(uri, self).await
+    }
+
+    /// Path of the file for a given artifact code
+    fn path_for(&self, code: &str) -> PathBuf {
+        let shard = code.get(2..4).unwrap_or("__");
+        self.root.join(shard).join(format!("{code}.trig"))
+    }
+}
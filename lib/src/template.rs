@@ -0,0 +1,497 @@
+use crate::constants::NP_TEMP_URI;
+use crate::error::NpError;
+use crate::nanopub::Nanopub;
+use crate::utils::term_value;
+use crate::vocab::{npx, nt, prov};
+use crate::NanopubBuilder;
+
+use oxrdf::{
+    dataset::GraphView,
+    vocab::{rdf, rdfs},
+    Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, NamedOrBlankNodeRef, Term, TermRef,
+    TripleRef,
+};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const NT_NS: &str = "https://w3id.org/np/o/ntemplate/";
+
+/// A placeholder of a template, to fill with a value
+#[derive(Clone, Debug, Serialize)]
+pub struct Placeholder {
+    pub iri: NamedNode,
+    /// Name used to give the value of the placeholder, the local name of its IRI
+    pub name: String,
+    /// Types of the placeholder, e.g. `nt:UriPlaceholder` or `nt:LiteralPlaceholder`
+    pub types: Vec<String>,
+    pub label: Option<String>,
+    /// Prefix added to the value when it does not start with it, e.g. `https://orcid.org/`
+    pub prefix: Option<String>,
+    /// Regex the value should match, without the prefix
+    pub regex: Option<String>,
+    pub possible_values: Vec<String>,
+}
+
+impl Placeholder {
+    /// The placeholder is filled with a literal
+    pub fn is_literal(&self) -> bool {
+        self.has_type(nt::LITERAL_PLACEHOLDER) || self.has_type(nt::LONG_LITERAL_PLACEHOLDER)
+    }
+
+    /// The placeholder is filled with a local name in the namespace of the new nanopub
+    pub fn is_local(&self) -> bool {
+        self.has_type(nt::LOCAL_RESOURCE)
+    }
+
+    /// The placeholder is filled with a resource introduced by the new nanopub
+    pub fn is_introduced(&self) -> bool {
+        self.has_type(nt::INTRODUCED_RESOURCE)
+    }
+
+    /// The placeholder is filled with one of its possible values
+    pub fn is_restricted_choice(&self) -> bool {
+        self.has_type(nt::RESTRICTED_CHOICE_PLACEHOLDER)
+    }
+
+    fn has_type(&self, type_iri: NamedNodeRef) -> bool {
+        self.types.iter().any(|t| t == type_iri.as_str())
+    }
+}
+
+/// A statement of a template, added to the assertion of the filled nanopub
+#[derive(Clone, Debug)]
+pub struct TemplateStatement {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+    /// Optional statements are skipped when one of their placeholders has no value
+    pub optional: bool,
+    pub order: Option<i64>,
+    /// The grouped statement this statement is part of, the statements of a group are filled together
+    pub group: Option<NamedOrBlankNode>,
+}
+
+/// An assertion template nanopub, as used by [Nanobench](https://github.com/knowledgepixels/nanobench),
+/// that can be filled with values to create a new nanopub.
+///
+/// # Examples
+///
+/// ```
+/// use std::{collections::HashMap, fs};
+/// use nanopub::{template::Template, Nanopub};
+///
+/// let np_rdf = fs::read_to_string("./tests/testsuite/valid/signed/nanobench_hasRead-template-v5.trig").unwrap();
+/// let template = Template::new(Nanopub::new(&np_rdf).unwrap()).unwrap();
+/// let values = HashMap::from([("paper".to_string(), "10.1038/sdata.2016.18".to_string())]);
+/// let np = template.fill(&values, "https://orcid.org/0000-0000-0000-0000").unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Template {
+    pub np: Nanopub,
+    pub label: Option<String>,
+    pub placeholders: Vec<Placeholder>,
+    /// Statements sorted by their order in the template
+    pub statements: Vec<TemplateStatement>,
+}
+
+impl Template {
+    /// Load a template from an assertion template nanopub
+    pub fn new(np: Nanopub) -> Result<Self, NpError> {
+        let assertion = np.assertion();
+        let template_node = NamedOrBlankNodeRef::from(np.info.assertion.as_ref());
+        if !assertion.contains(TripleRef::new(
+            template_node,
+            rdf::TYPE,
+            nt::ASSERTION_TEMPLATE,
+        )) {
+            return Err(NpError(format!(
+                "Invalid template: {} is not an assertion template",
+                np.info.uri.as_str()
+            )));
+        }
+        let label = assertion
+            .object_for_subject_predicate(template_node, rdfs::LABEL)
+            .and_then(literal_value);
+
+        // Placeholders are the resources with a placeholder type of the template vocabulary
+        let mut placeholders: Vec<Placeholder> = Vec::new();
+        for triple in assertion.triples_for_predicate(rdf::TYPE) {
+            let (NamedOrBlankNodeRef::NamedNode(iri), TermRef::NamedNode(type_iri)) =
+                (triple.subject, triple.object)
+            else {
+                continue;
+            };
+            let is_placeholder_type = type_iri
+                .as_str()
+                .strip_prefix(NT_NS)
+                .is_some_and(|t| t.ends_with("Placeholder"))
+                || type_iri == nt::LOCAL_RESOURCE
+                || type_iri == nt::INTRODUCED_RESOURCE;
+            if !is_placeholder_type || placeholders.iter().any(|p| p.iri == iri) {
+                continue;
+            }
+            let mut types: Vec<String> = assertion
+                .objects_for_subject_predicate(iri, rdf::TYPE)
+                .filter_map(|t| match t {
+                    TermRef::NamedNode(t) => Some(t.as_str().to_string()),
+                    _ => None,
+                })
+                .collect();
+            types.sort();
+            let mut possible_values: Vec<String> = assertion
+                .objects_for_subject_predicate(iri, nt::POSSIBLE_VALUE)
+                .filter_map(term_value)
+                .collect();
+            possible_values.sort();
+            placeholders.push(Placeholder {
+                iri: iri.into_owned(),
+                name: local_name(iri.as_str(), np.info.ns.as_str()),
+                types,
+                label: assertion
+                    .object_for_subject_predicate(iri, rdfs::LABEL)
+                    .and_then(literal_value),
+                prefix: assertion
+                    .object_for_subject_predicate(iri, nt::HAS_PREFIX)
+                    .and_then(literal_value),
+                regex: assertion
+                    .object_for_subject_predicate(iri, nt::HAS_REGEX)
+                    .and_then(literal_value),
+                possible_values,
+            });
+        }
+        placeholders.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut statements: Vec<TemplateStatement> = Vec::new();
+        for st in assertion
+            .objects_for_subject_predicate(template_node, nt::HAS_STATEMENT)
+            .filter_map(statement_node)
+        {
+            if !assertion.contains(TripleRef::new(st, rdf::TYPE, nt::GROUPED_STATEMENT)) {
+                statements.push(parse_statement(&assertion, st)?);
+                continue;
+            }
+            // The statements of a group get the order of the group, and are optional when the group is
+            let optional =
+                assertion.contains(TripleRef::new(st, rdf::TYPE, nt::OPTIONAL_STATEMENT));
+            let order = statement_order(&assertion, st);
+            let mut grouped: Vec<TemplateStatement> = assertion
+                .objects_for_subject_predicate(st, nt::HAS_STATEMENT)
+                .filter_map(statement_node)
+                .map(|grouped_st| parse_statement(&assertion, grouped_st))
+                .collect::<Result<_, _>>()?;
+            if grouped.is_empty() {
+                return Err(NpError(format!(
+                    "Invalid template: grouped statement {st} has no statement"
+                )));
+            }
+            grouped.sort_by_key(|grouped_st| grouped_st.order.unwrap_or(i64::MAX));
+            for mut grouped_st in grouped {
+                grouped_st.optional |= optional;
+                grouped_st.order = order;
+                grouped_st.group = Some(st.into_owned());
+                statements.push(grouped_st);
+            }
+        }
+        statements.sort_by_key(|st| st.order.unwrap_or(i64::MAX));
+        Ok(Self {
+            np,
+            label,
+            placeholders,
+            statements,
+        })
+    }
+
+    /// Get a placeholder from its name
+    pub fn placeholder(&self, name: &str) -> Option<&Placeholder> {
+        self.placeholders.iter().find(|p| p.name == name)
+    }
+
+    /// A placeholder is optional when it is only used in optional statements
+    pub fn is_optional(&self, placeholder: &Placeholder) -> bool {
+        self.statements
+            .iter()
+            .filter(|st| {
+                [&st.subject, &st.predicate, &st.object]
+                    .into_iter()
+                    .any(|t| matches!(t, Term::NamedNode(iri) if iri == &placeholder.iri))
+            })
+            .all(|st| st.optional)
+    }
+
+    /// Fill the placeholders of the template with the given values, indexed by placeholder name,
+    /// and create a new unsigned nanopub linked to the template with `nt:wasCreatedFromTemplate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the placeholders, statements with a missing optional value are skipped
+    /// * `creator` - The IRI of the creator of the nanopub, usually an ORCID
+    pub fn fill(
+        &self,
+        values: &HashMap<String, String>,
+        creator: &str,
+    ) -> Result<Nanopub, NpError> {
+        if let Some(name) = values.keys().find(|name| self.placeholder(name).is_none()) {
+            return Err(NpError(format!(
+                "Unknown placeholder {name} in template {}",
+                self.np.info.uri.as_str()
+            )));
+        }
+        let creator = NamedNode::new(creator)
+            .map_err(|e| NpError(format!("Invalid creator IRI {creator}: {e}")))?;
+        let mut builder = NanopubBuilder::new();
+        let mut introduced: Vec<NamedNode> = Vec::new();
+        for group in self
+            .statements
+            .chunk_by(|a, b| a.group.is_some() && a.group == b.group)
+        {
+            // A group is skipped when an optional value is missing and all its statements are optional
+            let group_optional = group.iter().all(|st| st.optional);
+            let mut triples = Vec::with_capacity(group.len());
+            for st in group {
+                match self.fill_statement(st, values, &creator)? {
+                    Some(triple) => triples.push((st, triple)),
+                    None if st.optional => {
+                        if group_optional {
+                            triples.clear();
+                            break;
+                        }
+                    }
+                    None => {
+                        let missing: Vec<&str> = [&st.subject, &st.predicate, &st.object]
+                            .into_iter()
+                            .filter_map(|t| self.term_placeholder(t))
+                            .map(|p| p.name.as_str())
+                            .filter(|name| values.get(*name).is_none_or(|v| v.is_empty()))
+                            .collect();
+                        return Err(NpError(format!(
+                            "Missing value for placeholder {}",
+                            missing.join(", ")
+                        )));
+                    }
+                }
+            }
+            for (st, (subject, predicate, object)) in triples {
+                // Keep track of the resources introduced by the nanopub
+                for (term, value) in [(&st.subject, &subject), (&st.object, &object)] {
+                    if let (Some(p), Term::NamedNode(iri)) = (self.term_placeholder(term), value) {
+                        if p.is_introduced() && !introduced.contains(iri) {
+                            introduced.push(iri.clone());
+                        }
+                    }
+                }
+                let subject = NamedOrBlankNode::try_from(subject)
+                    .map_err(|_| NpError("Invalid template: a subject is a literal".to_string()))?;
+                let predicate = NamedNode::try_from(predicate).map_err(|_| {
+                    NpError("Invalid template: a predicate is not an IRI".to_string())
+                })?;
+                builder = builder.add_assertion(subject, predicate, object);
+            }
+        }
+        builder = builder
+            .add_provenance(
+                NanopubBuilder::local("assertion"),
+                prov::WAS_ATTRIBUTED_TO,
+                creator.clone(),
+            )
+            .add_pubinfo(
+                NanopubBuilder::uri(),
+                nt::WAS_CREATED_FROM_TEMPLATE,
+                self.np.info.uri.clone(),
+            )
+            .with_creator(creator.as_str());
+        for iri in introduced {
+            builder = builder.add_pubinfo(NanopubBuilder::uri(), npx::INTRODUCES, iri);
+        }
+        // Reuse the prefixes of the template, except the ones of its namespace
+        for (prefix, ns) in &self.np.info.prefixes {
+            if !ns.starts_with(self.np.info.uri.as_str()) {
+                builder = builder.with_prefix(prefix, ns);
+            }
+        }
+        builder.build()
+    }
+
+    /// Fill the terms of a statement, `None` if one of its placeholders has no value
+    fn fill_statement(
+        &self,
+        st: &TemplateStatement,
+        values: &HashMap<String, String>,
+        creator: &NamedNode,
+    ) -> Result<Option<(Term, Term, Term)>, NpError> {
+        let subject = self.fill_term(&st.subject, values, creator)?;
+        let predicate = self.fill_term(&st.predicate, values, creator)?;
+        let object = self.fill_term(&st.object, values, creator)?;
+        Ok(subject
+            .zip(predicate)
+            .zip(object)
+            .map(|((s, p), o)| (s, p, o)))
+    }
+
+    /// Get the placeholder a term of a statement refers to
+    fn term_placeholder(&self, term: &Term) -> Option<&Placeholder> {
+        match term {
+            Term::NamedNode(iri) => self.placeholders.iter().find(|p| &p.iri == iri),
+            _ => None,
+        }
+    }
+
+    /// Replace a term of a template statement with its value, `None` if it is a placeholder without value
+    fn fill_term(
+        &self,
+        term: &Term,
+        values: &HashMap<String, String>,
+        creator: &NamedNode,
+    ) -> Result<Option<Term>, NpError> {
+        let Term::NamedNode(iri) = term else {
+            return Ok(Some(term.clone()));
+        };
+        if iri.as_ref() == nt::CREATOR {
+            return Ok(Some(creator.clone().into()));
+        }
+        if iri.as_ref() == nt::ASSERTION {
+            return Ok(Some(NanopubBuilder::local("assertion").into()));
+        }
+        if iri.as_ref() == nt::NANOPUB {
+            return Ok(Some(NanopubBuilder::uri().into()));
+        }
+        let Some(placeholder) = self.term_placeholder(term) else {
+            // Other resources of the template namespace are local to the new nanopub
+            if let Some(name) = iri.as_str().strip_prefix(self.np.info.ns.as_str()) {
+                let name = name.trim_start_matches(['#', '/', '.']);
+                return Ok(Some(NanopubBuilder::local(name).into()));
+            }
+            return Ok(Some(term.clone()));
+        };
+        let Some(value) = values.get(&placeholder.name).filter(|v| !v.is_empty()) else {
+            return Ok(None);
+        };
+        let unprefixed = placeholder
+            .prefix
+            .as_deref()
+            .and_then(|prefix| value.strip_prefix(prefix))
+            .unwrap_or(value);
+        if let Some(regex) = &placeholder.regex {
+            if !Regex::new(&format!("^(?:{regex})$"))?.is_match(unprefixed) {
+                return Err(NpError(format!(
+                    "Invalid value {value} for placeholder {}: it should match {regex}",
+                    placeholder.name
+                )));
+            }
+        }
+        if placeholder.is_literal() {
+            check_possible_value(placeholder, value)?;
+            return Ok(Some(Literal::new_simple_literal(value).into()));
+        }
+        if placeholder.is_local() {
+            if value.contains(['/', '#']) {
+                return Err(NpError(format!(
+                    "Invalid local name {value} for placeholder {}: it should not contain / or #",
+                    placeholder.name
+                )));
+            }
+            return NamedNode::new(format!("{NP_TEMP_URI}{value}"))
+                .map(|_| Some(NanopubBuilder::local(value).into()))
+                .map_err(|e| {
+                    NpError(format!(
+                        "Invalid local name {value} for placeholder {}: {e}",
+                        placeholder.name
+                    ))
+                });
+        }
+        let iri = match &placeholder.prefix {
+            Some(prefix) => format!("{prefix}{unprefixed}"),
+            None => value.to_string(),
+        };
+        check_possible_value(placeholder, &iri)?;
+        NamedNode::new(&iri)
+            .map(|iri| Some(iri.into()))
+            .map_err(|e| {
+                NpError(format!(
+                    "Invalid IRI {iri} for placeholder {}: {e}",
+                    placeholder.name
+                ))
+            })
+    }
+}
+
+/// Check the value of a restricted choice placeholder is one of its possible values
+fn check_possible_value(placeholder: &Placeholder, value: &str) -> Result<(), NpError> {
+    if !placeholder.is_restricted_choice() {
+        return Ok(());
+    }
+    if placeholder.possible_values.is_empty() {
+        return Err(NpError(format!(
+            "Unsupported template: the possible values of placeholder {} are not in the template",
+            placeholder.name
+        )));
+    }
+    if !placeholder.possible_values.iter().any(|v| v == value) {
+        return Err(NpError(format!(
+            "Invalid value {value} for placeholder {}: it should be one of {}",
+            placeholder.name,
+            placeholder.possible_values.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Node of a statement of the template
+fn statement_node(term: TermRef) -> Option<NamedOrBlankNodeRef> {
+    match term {
+        TermRef::NamedNode(st) => Some(st.into()),
+        TermRef::BlankNode(st) => Some(st.into()),
+        TermRef::Literal(_) => None,
+    }
+}
+
+/// Order of a statement in the template
+fn statement_order(assertion: &GraphView, st: NamedOrBlankNodeRef) -> Option<i64> {
+    assertion
+        .object_for_subject_predicate(st, nt::STATEMENT_ORDER)
+        .and_then(literal_value)
+        .and_then(|o| o.parse().ok())
+}
+
+/// Load a statement of the template, with its subject, predicate and object
+fn parse_statement(
+    assertion: &GraphView,
+    st: NamedOrBlankNodeRef,
+) -> Result<TemplateStatement, NpError> {
+    let part = |predicate| {
+        assertion
+            .object_for_subject_predicate(st, predicate)
+            .map(TermRef::into_owned)
+            .ok_or_else(|| {
+                NpError(format!(
+                    "Invalid template: statement {st} has no {}",
+                    predicate
+                ))
+            })
+    };
+    Ok(TemplateStatement {
+        subject: part(rdf::SUBJECT)?,
+        predicate: part(rdf::PREDICATE)?,
+        object: part(rdf::OBJECT)?,
+        optional: assertion.contains(TripleRef::new(st, rdf::TYPE, nt::OPTIONAL_STATEMENT)),
+        order: statement_order(assertion, st),
+        group: None,
+    })
+}
+
+/// Local name of an IRI in the given namespace, or after its last `#` or `/`
+fn local_name(iri: &str, ns: &str) -> String {
+    iri.strip_prefix(ns)
+        .map(|name| name.trim_start_matches(['#', '/', '.']))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| iri.rsplit(['#', '/']).next().unwrap_or(iri))
+        .to_string()
+}
+
+/// Get the value of a literal, `None` for IRIs and blank nodes
+fn literal_value(term: TermRef) -> Option<String> {
+    match term {
+        TermRef::Literal(lit) => Some(lit.value().to_string()),
+        _ => None,
+    }
+}
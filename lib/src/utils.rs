@@ -1,40 +1,128 @@
 use getrandom::fill;
-use oxjsonld::JsonLdParser;
+use oxjsonld::{JsonLdParser, JsonLdSerializer};
 use oxrdf::{
     Dataset, GraphNameRef, NamedNode, NamedNodeRef, NamedOrBlankNodeRef, QuadRef, TermRef,
 };
-use oxttl::{TriGParser, TriGSerializer};
+use oxttl::{NQuadsParser, NQuadsSerializer, TriGParser, TriGSerializer};
+use regex::Regex;
 use serde::Serialize;
 use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
 
-use crate::constants::LIST_SERVERS;
 use crate::error::NpError;
+use crate::servers::np_servers;
 
-/// Parse RDF from various format to a `Dataset` (trig, nquads, JSON-LD)
+/// RDF serialization formats supporting quads that can be used for nanopubs
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+pub enum RdfFormat {
+    TriG,
+    NQuads,
+    JsonLd,
+}
+
+impl RdfFormat {
+    /// Media type used for content negotiation
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            RdfFormat::TriG => "application/trig",
+            RdfFormat::NQuads => "application/n-quads",
+            RdfFormat::JsonLd => "application/ld+json",
+        }
+    }
+
+    /// Get the format from a media type, e.g. the `Content-Type` of a HTTP response. Parameters such as `charset` are ignored
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let media_type = media_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match media_type.as_str() {
+            "application/trig" | "application/x-trig" => Some(RdfFormat::TriG),
+            "application/n-quads" | "text/x-nquads" | "text/nquads" => Some(RdfFormat::NQuads),
+            "application/ld+json" => Some(RdfFormat::JsonLd),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RdfFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RdfFormat::TriG => write!(f, "trig"),
+            RdfFormat::NQuads => write!(f, "nquads"),
+            RdfFormat::JsonLd => write!(f, "jsonld"),
+        }
+    }
+}
+
+impl FromStr for RdfFormat {
+    type Err = NpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trig" => Ok(RdfFormat::TriG),
+            "nquads" | "nq" | "n-quads" => Ok(RdfFormat::NQuads),
+            "jsonld" | "json-ld" => Ok(RdfFormat::JsonLd),
+            _ => RdfFormat::from_media_type(s)
+                .ok_or_else(|| NpError(format!("Unsupported RDF format: {s}"))),
+        }
+    }
+}
+
+/// Parse RDF from various format to a `Dataset` (trig, nquads, JSON-LD), the format is guessed from the content
 pub fn parse_rdf(rdf: &str) -> Result<(Dataset, Vec<(String, String)>), NpError> {
-    let mut dataset = Dataset::new();
     // NOTE: an efficient way to differentiate between JSON-LD and TriG is to check if the string starts with '{' or '['
-    let prefixes: Vec<(String, String)> = if rdf.trim_start().starts_with(['{', '[']) {
-        let mut parser = JsonLdParser::new().for_reader(rdf.as_bytes());
-        parser.try_for_each(|q| {
-            dataset.insert(&q?);
-            Ok::<_, NpError>(())
-        })?;
-        parser
-            .prefixes()
-            .map(|(prefix, iri)| (prefix.to_owned(), iri.to_owned()))
-            .collect()
+    if rdf.trim_start().starts_with(['{', '[']) {
+        parse_rdf_as(rdf, RdfFormat::JsonLd)
     } else {
-        // The TriG parser handles nquads
-        let mut parser = TriGParser::new().for_reader(rdf.as_bytes());
-        parser.try_for_each(|q| {
-            dataset.insert(&q?);
-            Ok::<_, NpError>(())
-        })?;
-        parser
-            .prefixes()
-            .map(|(prefix, iri)| (prefix.to_owned(), iri.to_owned()))
-            .collect()
+        // Quads are not valid TriG, so fall back to the N-Quads parser, keeping the TriG error if both fail
+        parse_rdf_as(rdf, RdfFormat::TriG)
+            .or_else(|err| parse_rdf_as(rdf, RdfFormat::NQuads).map_err(|_| err))
+    }
+}
+
+/// Parse RDF in the given format to a `Dataset`
+pub fn parse_rdf_as(
+    rdf: &str,
+    format: RdfFormat,
+) -> Result<(Dataset, Vec<(String, String)>), NpError> {
+    let mut dataset = Dataset::new();
+    let prefixes: Vec<(String, String)> = match format {
+        RdfFormat::JsonLd => {
+            let mut parser = JsonLdParser::new().for_reader(rdf.as_bytes());
+            parser.try_for_each(|q| {
+                dataset.insert(&q?);
+                Ok::<_, NpError>(())
+            })?;
+            parser
+                .prefixes()
+                .map(|(prefix, iri)| (prefix.to_owned(), iri.to_owned()))
+                .collect()
+        }
+        RdfFormat::NQuads => {
+            NQuadsParser::new()
+                .for_reader(rdf.as_bytes())
+                .try_for_each(|q| {
+                    dataset.insert(&q?);
+                    Ok::<_, NpError>(())
+                })?;
+            Vec::new()
+        }
+        RdfFormat::TriG => {
+            let mut parser = TriGParser::new().for_reader(rdf.as_bytes());
+            parser.try_for_each(|q| {
+                dataset.insert(&q?);
+                Ok::<_, NpError>(())
+            })?;
+            parser
+                .prefixes()
+                .map(|(prefix, iri)| (prefix.to_owned(), iri.to_owned()))
+                .collect()
+        }
     };
     Ok((dataset, prefixes))
 }
@@ -46,9 +134,19 @@ pub fn serialize_rdf(
     ns: &str,
     prefixes: &[(String, String)],
 ) -> Result<String, NpError> {
-    let mut serializer = TriGSerializer::new();
+    serialize_rdf_as(dataset, uri, ns, prefixes, RdfFormat::TriG)
+}
+
+/// Serialize RDF dataset to the given format, N-Quads do not use prefixes
+pub fn serialize_rdf_as(
+    dataset: &Dataset,
+    uri: &str,
+    ns: &str,
+    prefixes: &[(String, String)],
+    format: RdfFormat,
+) -> Result<String, NpError> {
     // Add a set of default prefixes
-    for (prefix_name, prefix_iri) in [
+    let mut all_prefixes: Vec<(&str, &str)> = vec![
         ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
         ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
         ("xsd", "http://www.w3.org/2001/XMLSchema#"),
@@ -57,38 +155,99 @@ pub fn serialize_rdf(
         ("np", "http://www.nanopub.org/nschema#"),
         ("npx", "http://purl.org/nanopub/x/"),
         ("orcid", "https://orcid.org/"),
-    ] {
-        serializer = serializer.with_prefix(prefix_name, prefix_iri)?;
-    }
+    ];
     // Add or override prefixes from parsed source document (removing empty prefix which would be used as base)
-    for (prefix_name, prefix_iri) in prefixes.iter().filter(|(name, _)| !name.is_empty()) {
-        serializer = serializer.with_prefix(prefix_name, prefix_iri)?;
-    }
+    all_prefixes.extend(
+        prefixes
+            .iter()
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, iri)| (name.as_str(), iri.as_str())),
+    );
     // Add or override core prefixes
-    serializer = serializer
-        .with_prefix("this", uri)?
-        .with_prefix("sub", ns)?;
-    let mut serializer = serializer.for_writer(Vec::new());
+    all_prefixes.extend([("this", uri), ("sub", ns)]);
     // NOTE: we need to sort ourself the quads
     let mut quads: Vec<QuadRef<'_>> = dataset.iter().collect();
     quads.sort_by(quad_compare);
-    for quad in &quads {
-        serializer.serialize_quad(*quad)?;
-    }
-    Ok(String::from_utf8(serializer.finish()?)?)
+    let bytes = match format {
+        RdfFormat::TriG => {
+            let mut serializer = TriGSerializer::new();
+            for (prefix_name, prefix_iri) in all_prefixes {
+                serializer = serializer.with_prefix(prefix_name, prefix_iri)?;
+            }
+            let mut serializer = serializer.for_writer(Vec::new());
+            for quad in &quads {
+                serializer.serialize_quad(*quad)?;
+            }
+            serializer.finish()?
+        }
+        RdfFormat::NQuads => {
+            let mut serializer = NQuadsSerializer::new().for_writer(Vec::new());
+            for quad in &quads {
+                serializer.serialize_quad(*quad)?;
+            }
+            serializer.finish()
+        }
+        RdfFormat::JsonLd => {
+            let mut serializer = JsonLdSerializer::new();
+            for (prefix_name, prefix_iri) in all_prefixes {
+                serializer = serializer.with_prefix(prefix_name, prefix_iri)?;
+            }
+            let mut serializer = serializer.for_writer(Vec::new());
+            for quad in &quads {
+                serializer.serialize_quad(*quad)?;
+            }
+            serializer.finish()?
+        }
+    };
+    Ok(String::from_utf8(bytes)?)
 }
 
-/// Return a Nanopub server, the main one or one picked randomly from the list of available servers
-pub fn get_np_server(random: bool) -> &'static str {
-    if !random {
-        return LIST_SERVERS[0];
+/// Number of the best ranked servers a random server is picked from
+const RANDOM_AMONG_BEST: usize = 3;
+
+/// Return a Nanopub server, the best ranked one or one picked randomly among the best ranked servers.
+///
+/// The servers are the ones set with [`set_np_servers`](crate::servers::set_np_servers),
+/// e.g. loaded from the nanopub monitor, defaults to [`LIST_SERVERS`](crate::constants::LIST_SERVERS)
+pub fn get_np_server(random: bool) -> String {
+    let mut servers = np_servers();
+    if random {
+        let best = servers.len().min(RANDOM_AMONG_BEST);
+        servers.swap_remove(random_index(best))
+    } else {
+        servers.swap_remove(0)
     }
+}
+
+/// Random index in a list of the given length
+fn random_index(len: usize) -> usize {
     // Generate a random number
     let mut buf = [0u8; 4];
     fill(&mut buf).expect("Failed to generate random number");
     let num = u32::from_ne_bytes(buf);
-    let index = num as usize % LIST_SERVERS.len();
-    LIST_SERVERS[index]
+    num as usize % len
+}
+
+static RE_ARTIFACT_CODE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^a-zA-Z0-9\-_])(RA[a-zA-Z0-9\-_]{43})(?:[#/\.][^/]*)?$")
+        .expect("valid artifact code regex")
+});
+
+/// Extract the trusty artifact code (e.g. `RAe_LF_8hl...`) from a nanopub URI, or from a bare artifact code
+pub fn extract_artifact_code(uri: &str) -> Option<&str> {
+    RE_ARTIFACT_CODE
+        .captures(uri.trim())
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str())
+}
+
+/// Get the value of a term as string: the IRI of named nodes, or the value of literals
+pub(crate) fn term_value(term: TermRef<'_>) -> Option<String> {
+    match term {
+        TermRef::NamedNode(iri) => Some(iri.as_str().to_string()),
+        TermRef::Literal(literal) => Some(literal.value().to_string()),
+        _ => None,
+    }
 }
 
 /// Extract IRI as `String` from subject term, or error if blank node
@@ -46,6 +46,39 @@ pub mod npx {
     /// npx:declaredBy rdf:type rdf:Property; rdfs:domain npx:KeyDeclaration; rdfs:range foaf:Agent.
     pub const DECLARED_BY: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/declaredBy");
+    /// npx:hasNanopubType rdf:type rdf:Property; rdfs:domain np:Nanopublication.
+    pub const HAS_NANOPUB_TYPE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/hasNanopubType");
+    /// npx:retracts rdf:type rdf:Property; rdfs:range np:Nanopublication.
+    pub const RETRACTS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/retracts");
+    /// npx:supersedes rdf:type rdf:Property; rdfs:domain np:Nanopublication; rdfs:range np:Nanopublication.
+    pub const SUPERSEDES: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/supersedes");
+    /// npx:introduces rdf:type rdf:Property; rdfs:domain np:Nanopublication.
+    pub const INTRODUCES: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/introduces");
+    /// npx:embeds rdf:type rdf:Property; rdfs:domain np:Nanopublication.
+    pub const EMBEDS: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/embeds");
+    /// npx:NanopubIndex rdfs:subClassOf np:Nanopublication.
+    pub const NANOPUB_INDEX: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/NanopubIndex");
+    /// npx:IncompleteIndex rdfs:subClassOf npx:NanopubIndex.
+    pub const INCOMPLETE_INDEX: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/IncompleteIndex");
+    /// npx:IndexAssertion rdfs:subClassOf np:Assertion.
+    pub const INDEX_ASSERTION: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/IndexAssertion");
+    /// npx:includesElement rdf:type rdf:Property; rdfs:domain npx:NanopubIndex; rdfs:range np:Nanopublication.
+    pub const INCLUDES_ELEMENT: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/includesElement");
+    /// npx:appendsIndex rdf:type rdf:Property; rdfs:domain npx:NanopubIndex; rdfs:range npx:NanopubIndex.
+    pub const APPENDS_INDEX: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/appendsIndex");
+    /// npx:hasSubIndex rdf:type rdf:Property; rdfs:domain npx:NanopubIndex; rdfs:range npx:NanopubIndex.
+    pub const HAS_SUB_INDEX: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/nanopub/x/hasSubIndex");
 }
 
 pub mod pav {
@@ -55,6 +88,12 @@ pub mod pav {
     /// Created by.
     pub const CREATED_BY: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://purl.org/pav/createdBy");
+    /// Authored by.
+    pub const AUTHORED_BY: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/pav/authoredBy");
+    /// Created on.
+    pub const CREATED_ON: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/pav/createdOn");
 }
 
 pub mod foaf {
@@ -76,6 +115,12 @@ pub mod dct {
     /// Creator.
     pub const CREATOR: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://purl.org/dc/terms/creator");
+    /// Title.
+    pub const TITLE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/dc/terms/title");
+    /// Description.
+    pub const DESCRIPTION: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://purl.org/dc/terms/description");
 }
 
 pub mod prov {
@@ -85,4 +130,67 @@ pub mod prov {
     ///  Attribution is the ascribing of an entity to an agent.
     pub const WAS_ATTRIBUTED_TO: NamedNodeRef<'_> =
         NamedNodeRef::new_unchecked("http://www.w3.org/ns/prov#wasAttributedTo");
+    /// Generation is the completion of production of a new entity by an activity.
+    pub const GENERATED_AT_TIME: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("http://www.w3.org/ns/prov#generatedAtTime");
+}
+
+pub mod nt {
+    //! [Nanopub templates](https://w3id.org/np/o/ntemplate/) vocabulary (subset only).
+    use oxrdf::NamedNodeRef;
+
+    /// A template for the assertion of a nanopub.
+    pub const ASSERTION_TEMPLATE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/AssertionTemplate");
+    /// A statement of the template, always part of the filled assertion unless optional.
+    pub const HAS_STATEMENT: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/hasStatement");
+    /// A statement that is only added when its placeholders are filled.
+    pub const OPTIONAL_STATEMENT: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/OptionalStatement");
+    /// A group of statements, filled together.
+    pub const GROUPED_STATEMENT: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/GroupedStatement");
+    /// Order of a statement in the template.
+    pub const STATEMENT_ORDER: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/statementOrder");
+    /// A placeholder filled with a literal.
+    pub const LITERAL_PLACEHOLDER: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/LiteralPlaceholder");
+    /// A placeholder filled with a long literal.
+    pub const LONG_LITERAL_PLACEHOLDER: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/LongLiteralPlaceholder");
+    /// A placeholder filled with an IRI.
+    pub const URI_PLACEHOLDER: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/UriPlaceholder");
+    /// A placeholder filled with one of its possible values.
+    pub const RESTRICTED_CHOICE_PLACEHOLDER: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/RestrictedChoicePlaceholder");
+    /// A resource in the namespace of the filled nanopub.
+    pub const LOCAL_RESOURCE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/LocalResource");
+    /// A resource introduced by the filled nanopub.
+    pub const INTRODUCED_RESOURCE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/IntroducedResource");
+    /// Prefix added to the value of a placeholder.
+    pub const HAS_PREFIX: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/hasPrefix");
+    /// Regex the value of a placeholder should match.
+    pub const HAS_REGEX: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/hasRegex");
+    /// A possible value of a placeholder.
+    pub const POSSIBLE_VALUE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/possibleValue");
+    /// Replaced by the creator of the filled nanopub.
+    pub const CREATOR: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/CREATOR");
+    /// Replaced by the assertion of the filled nanopub.
+    pub const ASSERTION: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/ASSERTION");
+    /// Replaced by the filled nanopub.
+    pub const NANOPUB: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/NANOPUB");
+    /// Links a nanopub to the template it was created from.
+    pub const WAS_CREATED_FROM_TEMPLATE: NamedNodeRef<'_> =
+        NamedNodeRef::new_unchecked("https://w3id.org/np/o/ntemplate/wasCreatedFromTemplate");
 }
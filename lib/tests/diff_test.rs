@@ -0,0 +1,67 @@
+use nanopub::{diff::NpDiff, nanopub::NpPart, vocab::npx, Nanopub, ProfileBuilder};
+use oxrdf::{Literal, NamedNodeRef, Triple, TripleRef};
+use std::{error::Error, fs};
+
+fn get_np(path: &str) -> Result<Nanopub, Box<dyn Error>> {
+    Ok(Nanopub::new(&fs::read_to_string(path)?)?)
+}
+
+#[test]
+fn diff_same_nanopub() -> Result<(), Box<dyn Error>> {
+    let np = get_np("./tests/resources/signed.simple1-rsa.trig")?;
+    let diff = NpDiff::new(&np, &np);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "");
+    Ok(())
+}
+
+#[test]
+fn diff_signed_nanopub() -> Result<(), Box<dyn Error>> {
+    // The trusty URI of the signed nanopub does not show as a change, only the signature is added
+    let old = get_np("./tests/resources/simple1-rsa.trig")?;
+    let new = get_np("./tests/resources/signed.simple1-rsa.trig")?;
+    let diff = NpDiff::new(&old, &new);
+    assert!(diff.graph(NpPart::Head).is_empty());
+    assert!(diff.graph(NpPart::Assertion).is_empty());
+    assert!(diff.graph(NpPart::Provenance).is_empty());
+    let pubinfo = diff.graph(NpPart::PubInfo);
+    assert!(pubinfo.removed.is_empty());
+    assert_eq!(pubinfo.added.len(), 4);
+    assert!(pubinfo
+        .added
+        .iter()
+        .any(|t| t.predicate.as_ref() == npx::HAS_SIGNATURE));
+    Ok(())
+}
+
+#[test]
+fn diff_updated_assertion() -> Result<(), Box<dyn Error>> {
+    let profile = ProfileBuilder::new(fs::read_to_string("./tests/resources/id_rsa")?).build()?;
+    let old = get_np("./tests/resources/signed.simple1-rsa.trig")?;
+    let mut new = old.clone().unsign()?;
+    let mosquito = NamedNodeRef::new_unchecked("http://example.org/mosquito");
+    let transmits = NamedNodeRef::new_unchecked("http://example.org/transmits");
+    let malaria = NamedNodeRef::new_unchecked("http://example.org/malaria");
+    new.remove_triple(
+        NpPart::Assertion,
+        TripleRef::new(mosquito, transmits, malaria),
+    )?;
+    let dengue = Triple::new(mosquito, transmits, Literal::new_simple_literal("dengue"));
+    new.add_triple(NpPart::Assertion, dengue.as_ref())?;
+    let new = new.sign(&profile)?;
+    assert_ne!(new.info.uri, old.info.uri);
+
+    let diff = NpDiff::new(&old, &new);
+    let assertion = diff.graph(NpPart::Assertion);
+    assert_eq!(assertion.added, vec![dengue]);
+    assert_eq!(
+        assertion.removed,
+        vec![Triple::new(mosquito, transmits, malaria)]
+    );
+    assert!(diff.graph(NpPart::Head).is_empty());
+    assert!(diff.graph(NpPart::Provenance).is_empty());
+    assert!(diff
+        .to_string()
+        .contains("+ <http://example.org/mosquito> <http://example.org/transmits> \"dengue\""));
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use nanopub::{
+    cache::{MemoryCache, NpCache},
+    index::{IndexBuilder, NpIndex},
+    vocab::npx,
+    Nanopub, NanopubBuilder, NpProfile, ProfileBuilder,
+};
+use oxrdf::{vocab::rdf, Literal};
+use std::{error::Error, fs};
+
+fn get_profile() -> Result<NpProfile, Box<dyn Error>> {
+    Ok(
+        ProfileBuilder::new(fs::read_to_string("./tests/resources/id_rsa")?)
+            .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+            .build()?,
+    )
+}
+
+/// Sign some nanopubs to index, and add them to the cache
+fn sign_elements(
+    count: usize,
+    profile: &NpProfile,
+    cache: &MemoryCache,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut uris = Vec::new();
+    for i in 0..count {
+        let np = NanopubBuilder::new()
+            .add_assertion(
+                NanopubBuilder::local("claim"),
+                rdf::VALUE,
+                Literal::new_simple_literal(i.to_string()),
+            )
+            .add_provenance(
+                NanopubBuilder::local("assertion"),
+                rdf::TYPE,
+                NanopubBuilder::local("Claim"),
+            )
+            .add_pubinfo(
+                NanopubBuilder::uri(),
+                rdf::TYPE,
+                NanopubBuilder::local("Example"),
+            )
+            .build()?
+            .sign(profile)?;
+        cache.put(&np)?;
+        uris.push(np.info.uri.as_str().to_string());
+    }
+    Ok(uris)
+}
+
+#[test]
+fn read_index() -> Result<(), Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/testsuite/valid/trusty/generif-aida-index.trig")?;
+    let index = NpIndex::new(Nanopub::new(&np_rdf)?.check()?)?;
+    assert_eq!(index.elements.len(), 26);
+    assert!(index.sub_indexes.is_empty());
+    assert_eq!(
+        index.appends.as_deref(),
+        Some("http://np.inn.ac/RAuOJNR2pardA59l-d_eUnl7gRLr_vYfXb1vsGuaKwuis")
+    );
+    assert!(index.complete);
+    assert_eq!(
+        index.title.as_deref(),
+        Some("AIDA Nanopubs extracted from GeneRIF")
+    );
+
+    // Only index nanopubs can be loaded
+    let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
+    assert!(NpIndex::new(Nanopub::new(&np_rdf)?).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn build_and_traverse_index() -> Result<(), Box<dyn Error>> {
+    let profile = get_profile()?;
+    let cache = MemoryCache::new(100);
+    let elements = sign_elements(5, &profile, &cache)?;
+
+    // Indexes are split according to the max size, each appending the previous one
+    let indexes = IndexBuilder::new()
+        .add_elements(&elements)
+        .with_title("Test index")
+        .with_max_size(2)
+        .build(&profile)?;
+    assert_eq!(indexes.len(), 3);
+    for np in &indexes {
+        cache.put(np)?;
+    }
+    let first = NpIndex::new(indexes[0].clone())?;
+    assert!(!first.complete);
+    assert!(first.appends.is_none());
+    assert!(first.title.is_none());
+    assert_eq!(first.elements.len(), 2);
+    let index = NpIndex::new(indexes[2].clone())?;
+    assert!(index.complete);
+    assert_eq!(index.title.as_deref(), Some("Test index"));
+    assert_eq!(index.elements.len(), 1);
+    assert_eq!(index.appends.as_deref(), Some(indexes[1].info.uri.as_str()));
+    assert!(index
+        .np
+        .info
+        .types
+        .contains(&npx::NANOPUB_INDEX.as_str().to_string()));
+
+    // Traversal follows the appended indexes
+    let mut uris = index.element_uris(Some(&cache)).await?;
+    uris.sort();
+    let mut expected = elements.clone();
+    expected.sort();
+    assert_eq!(uris, expected);
+    assert_eq!(index.fetch_elements(Some(&cache)).await?.len(), 5);
+    // Without cache the appended indexes are fetched, and they are not published
+    assert_eq!(first.element_uris(None).await?, first.elements);
+    assert!(index.element_uris(None).await.is_err());
+
+    // Sub-indexes elements are included, before the elements of the index
+    let others = sign_elements(1, &profile, &cache)?;
+    let top = IndexBuilder::new()
+        .add_sub_index(index.np.info.uri.as_str())
+        .add_elements(&others)
+        .build(&profile)?;
+    assert_eq!(top.len(), 1);
+    let top = NpIndex::new(top[0].clone())?;
+    assert_eq!(
+        top.sub_indexes,
+        vec![index.np.info.uri.as_str().to_string()]
+    );
+    let uris = top.element_uris(Some(&cache)).await?;
+    assert_eq!(uris.len(), 6);
+    assert_eq!(uris.last(), others.last());
+    Ok(())
+}
+
+#[test]
+fn build_empty_index() -> Result<(), Box<dyn Error>> {
+    assert!(IndexBuilder::new().build(&get_profile()?).is_err());
+    Ok(())
+}
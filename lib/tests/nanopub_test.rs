@@ -1,15 +1,54 @@
+use chrono::{DateTime, Utc};
 use nanopub::{
-    constants::TEST_SERVER, extract::extract_np_info, get_np_server, nanopub::create_base_dataset,
-    network::publish_np, profile::gen_keys, sign::normalize_dataset, utils::parse_rdf, Nanopub,
-    ProfileBuilder,
+    builder::parse_triple,
+    cache::{MemoryCache, NpCache},
+    constants::{LIST_SERVERS, TEST_SERVER},
+    extract::extract_np_info,
+    get_np_server,
+    nanopub::{create_base_dataset, NpPart},
+    network::{publish_np, resolve_np_urls},
+    profile::gen_keys,
+    sign::normalize_dataset,
+    utils::extract_artifact_code,
+    utils::parse_rdf,
+    utils::{parse_rdf_as, RdfFormat},
+    vocab::{dct, npx, pav, prov},
+    Nanopub, NanopubBuilder, NanopubStore, ProfileBuilder, SignOptions,
 };
-use oxrdf::{Dataset, GraphNameRef, NamedNodeRef, QuadRef};
-use std::{error::Error, fs};
+use oxrdf::{
+    vocab::{rdf, rdfs},
+    BlankNode, Dataset, GraphNameRef, Literal, NamedNodeRef, QuadRef, TripleRef,
+};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::{error::Error, fs, thread};
 
 fn get_test_key() -> String {
     fs::read_to_string("./tests/resources/id_rsa").unwrap()
 }
 
+/// Start a local HTTP server answering a single request with the given status, content type and body
+fn serve_once(status: &str, content_type: &str, body: &str) -> String {
+    serve_once_at("np", status, content_type, body)
+}
+
+/// Start a local HTTP server answering a single request at the given path
+fn serve_once_at(path: &str, status: &str, content_type: &str, body: &str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/{path}", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 4096];
+        let _ = stream.read(&mut buf);
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    url
+}
+
 #[tokio::test]
 async fn publish_nanopub_simple_rsa() -> Result<(), Box<dyn Error>> {
     let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
@@ -110,6 +149,42 @@ fn load_profile_yml() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn save_profile() -> Result<(), Box<dyn Error>> {
+    let profile = ProfileBuilder::new(get_test_key())
+        .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+        .with_name("Test Person".to_string())
+        .build()?;
+    let tmp = tempfile::tempdir()?;
+    let dir = tmp.path();
+    let profile_path = profile.save(dir)?;
+    assert!(dir.join("id_rsa").exists() && dir.join("id_rsa.pub").exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(dir.join("id_rsa"))?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let loaded = ProfileBuilder::from_file(profile_path.to_str().unwrap())?;
+    assert_eq!(loaded.private_key, profile.private_key);
+    assert_eq!(loaded.public_key, profile.public_key);
+    assert_eq!(loaded.orcid_id, profile.orcid_id);
+    assert_eq!(loaded.name.as_deref(), Some("Test Person"));
+    assert!(loaded.introduction_nanopub_uri.is_none());
+
+    // Values are quoted, so they cannot add keys to the profile.yml
+    let name = "Name: \"quoted\" # not a comment\nprivate_key: /etc/passwd";
+    let profile = ProfileBuilder::new(get_test_key())
+        .with_name(name.to_string())
+        .build()?;
+    let profile_path = profile.save(dir)?;
+    let loaded = ProfileBuilder::from_file(profile_path.to_str().unwrap())?;
+    assert_eq!(loaded.name.as_deref(), Some(name));
+    assert_eq!(loaded.private_key, profile.private_key);
+    assert!(loaded.orcid_id.is_none());
+    Ok(())
+}
+
 #[test]
 fn check_valid_unsigned() -> Result<(), Box<dyn Error>> {
     let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
@@ -155,6 +230,30 @@ fn check_nanopub_test_blank() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn verify_check_result() -> Result<(), Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?;
+    let result = Nanopub::new(&np_rdf)?.verify()?;
+    assert!(result.valid && result.trusty && result.signed);
+    assert_eq!(result.algorithm, "RSA");
+    assert!(result.error.is_none());
+    assert_eq!(result.to_string(), "1 trusty with signature");
+
+    let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
+    let result = Nanopub::new(&np_rdf)?.verify()?;
+    assert!(result.valid && !result.trusty && !result.signed);
+
+    // Changing the assertion breaks the trusty hash
+    let tampered = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?
+        .replace("ex:malaria", "ex:dengue");
+    let np = Nanopub::new(&tampered)?;
+    let result = np.verify()?;
+    assert!(!result.valid && !result.trusty);
+    assert!(result.error.is_some());
+    assert!(np.check().is_err());
+    Ok(())
+}
+
 #[test]
 fn test_get_np_server() -> Result<(), Box<dyn Error>> {
     let _np_server = get_np_server(true);
@@ -265,3 +364,595 @@ async fn publish_from_scratch() -> Result<(), Box<dyn Error>> {
     // assert!(res.is_err());
     Ok(())
 }
+
+#[test]
+fn test_extract_artifact_code() -> Result<(), Box<dyn Error>> {
+    let code = "RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4";
+    assert_eq!(extract_artifact_code(code), Some(code));
+    assert_eq!(
+        extract_artifact_code(&format!("https://w3id.org/np/{code}")),
+        Some(code)
+    );
+    assert_eq!(
+        extract_artifact_code(&format!("https://w3id.org/np/{code}/assertion")),
+        Some(code)
+    );
+    assert_eq!(
+        extract_artifact_code("http://purl.org/nanopub/temp/mynanopub"),
+        None
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn nanopub_store() -> Result<(), Box<dyn Error>> {
+    let store_dir = std::env::temp_dir().join("nanopub-test-store");
+    let _ = fs::remove_dir_all(&store_dir);
+    let store = NanopubStore::new(&store_dir)?;
+    let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?;
+    let np = Nanopub::new(&np_rdf)?;
+    let uri = np.info.uri.as_str().to_string();
+    assert!(!store.contains(&uri));
+    assert!(store.get(&uri)?.is_none());
+
+    let path = store.put(&np)?;
+    assert!(path.exists());
+    assert!(store.contains(&uri));
+    assert!(store.contains(&np.info.trusty_hash));
+    let stored = store.get(&uri)?.expect("Nanopub should be in the store");
+    assert_eq!(stored.info.trusty_hash, np.info.trusty_hash);
+    assert_eq!(store.iter()?.count(), 1);
+
+    // Stored trusty nanopubs are returned without touching the network
+    let fetched = store.fetch(&uri).await?;
+    assert_eq!(fetched.info.published, Some(uri));
+
+    // Only trusty nanopubs passing the check can be stored
+    let unsigned = Nanopub::new(&fs::read_to_string("./tests/resources/simple1-rsa.trig")?)?;
+    assert!(store.put(&unsigned).is_err());
+    let tampered = np_rdf.replace("ex:malaria", "ex:dengue");
+    assert!(store.put(&Nanopub::new(&tampered)?).is_err());
+    assert_eq!(store.iter()?.count(), 1);
+    fs::remove_dir_all(&store_dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn memory_cache() -> Result<(), Box<dyn Error>> {
+    let cache = MemoryCache::new(1);
+    let np1 = Nanopub::new(&fs::read_to_string(
+        "./tests/resources/signed.simple1-rsa.trig",
+    )?)?;
+    let np2 = Nanopub::new(&fs::read_to_string(
+        "./tests/testsuite/valid/trusty/trusty1.trig",
+    )?)?;
+    cache.put(&np1)?;
+    assert!(cache.get(np1.info.uri.as_str())?.is_some());
+    // Cached nanopubs are returned without fetching
+    let fetched = Nanopub::fetch_cached(np1.info.uri.as_str(), &cache).await?;
+    assert_eq!(fetched.info.trusty_hash, np1.info.trusty_hash);
+    assert!(fetched.info.published.is_some());
+
+    // Least recently used nanopub is evicted when capacity is reached
+    cache.put(&np2)?;
+    assert_eq!(cache.len(), 1);
+    assert!(cache.get(&np1.info.trusty_hash)?.is_none());
+    assert!(cache.get(&np2.info.trusty_hash)?.is_some());
+
+    let unsigned = Nanopub::new(&fs::read_to_string("./tests/resources/simple1-rsa.trig")?)?;
+    assert!(cache.put(&unsigned).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_content_negotiation() -> Result<(), Box<dyn Error>> {
+    // Parse using the Content-Type of the response
+    let np_nq = fs::read_to_string("./tests/testsuite/valid/plain/simple1.nq")?;
+    let url = serve_once("200 OK", "application/n-quads; charset=utf-8", &np_nq);
+    let np = Nanopub::fetch_as(&url, RdfFormat::NQuads).await?;
+    assert_eq!(np.info.published, Some(url));
+    // The format is guessed from the content when the Content-Type is generic
+    let url = serve_once("200 OK", "text/plain", &np_nq);
+    assert!(Nanopub::fetch(&url).await.is_ok());
+
+    // Clear errors for HTML pages, other content types, and failed requests
+    let url = serve_once("200 OK", "text/html", "<html></html>");
+    let err = Nanopub::fetch(&url).await.unwrap_err();
+    assert!(err.to_string().contains("did not return RDF"), "{err}");
+    let url = serve_once("200 OK", "application/xml", "<rdf:RDF/>");
+    let err = Nanopub::fetch(&url).await.unwrap_err();
+    assert!(err.to_string().contains("application/xml"), "{err}");
+    let url = serve_once("404 Not Found", "text/plain", "Not found");
+    let err = Nanopub::fetch(&url).await.unwrap_err();
+    assert!(err.to_string().contains("404"), "{err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_unchecked_invalid() -> Result<(), Box<dyn Error>> {
+    // The trusty hash of this nanopub does not match, it is only returned when not checked
+    let np_rdf = fs::read_to_string("./tests/testsuite/invalid/trusty/trusty1.trig")?;
+    let code = "RAPpJU5UOB4pavfWyk7FE3WQiam5yBpmIlviAQWtBSC4M";
+    let url = serve_once_at(code, "200 OK", "application/trig", &np_rdf);
+    let np = Nanopub::fetch_unchecked(&url).await?;
+    assert_eq!(np.info.trusty_hash, code);
+    assert!(np.verify()?.error.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_rdf_format() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        RdfFormat::from_media_type("application/trig; charset=utf-8"),
+        Some(RdfFormat::TriG)
+    );
+    assert_eq!(RdfFormat::from_media_type("text/html"), None);
+    // Plain JSON is not always JSON-LD, its format is guessed from the content
+    assert_eq!(RdfFormat::from_media_type("application/json"), None);
+    assert_eq!("nq".parse::<RdfFormat>()?, RdfFormat::NQuads);
+    assert!("xml".parse::<RdfFormat>().is_err());
+    let np_nq = fs::read_to_string("./tests/testsuite/valid/plain/simple1.nq")?;
+    let (dataset, _prefixes) = parse_rdf_as(&np_nq, RdfFormat::NQuads)?;
+    assert!(extract_np_info(&dataset, Vec::new()).is_ok());
+    // The format of N-Quads is guessed too
+    assert!(Nanopub::new(&np_nq).is_ok());
+
+    // Serialize a signed nanopub to each format, it stays valid once parsed again
+    let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?;
+    let np = Nanopub::new(&np_rdf)?;
+    for format in [RdfFormat::TriG, RdfFormat::NQuads, RdfFormat::JsonLd] {
+        let (dataset, _prefixes) = parse_rdf_as(&np.rdf_as(format)?, format)?;
+        assert!(Nanopub::new(dataset)?.verify()?.valid, "{format}");
+    }
+    assert!(np.rdf_as(RdfFormat::JsonLd)?.starts_with('{'));
+    Ok(())
+}
+
+#[test]
+fn convert_keeps_prefixes() -> Result<(), Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?;
+    let np = Nanopub::new(&np_rdf)?;
+    // The prefixes are kept in the JSON-LD context, and are back when converting to TriG
+    let (_dataset, prefixes) = parse_rdf_as(&np.rdf_as(RdfFormat::JsonLd)?, RdfFormat::JsonLd)?;
+    assert!(prefixes.contains(&("sub".to_string(), np.info.ns.as_str().to_string())));
+    let np_jsonld = Nanopub::new(np.rdf_as(RdfFormat::JsonLd)?.as_str())?;
+    let np_trig = np_jsonld.rdf()?;
+    assert!(np_trig.contains(&format!("@prefix sub: <{}> .", np.info.ns.as_str())));
+    assert!(np_trig.contains("@prefix np: <http://www.nanopub.org/nschema#> ."));
+    Ok(())
+}
+
+#[test]
+fn nanopub_warnings() -> Result<(), Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?;
+    assert!(Nanopub::new(&np_rdf)?.warnings().is_empty());
+    let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
+    let warnings = Nanopub::new(&np_rdf)?.warnings();
+    assert!(warnings.contains(&"not signed.".to_string()));
+    assert!(warnings.contains(&"no trusty hash in the nanopub URI.".to_string()));
+
+    // All the structure problems are reported, instead of failing on the first one
+    let np_rdf = fs::read_to_string("./tests/testsuite/invalid/plain/emptyprov.trig")?;
+    let np = Nanopub::new(&np_rdf)?;
+    assert!(np.is_valid().is_err());
+    assert!(np
+        .warnings()
+        .contains(&"no triples in the provenance graph.".to_string()));
+    Ok(())
+}
+
+#[test]
+fn nanopub_references() -> Result<(), Box<dyn Error>> {
+    let np_rdf =
+        fs::read_to_string("./tests/testsuite/valid/signed/nanobench_hasRead-template-v5.trig")?;
+    let np = Nanopub::new(&np_rdf)?;
+    assert_eq!(
+        np.references(),
+        vec![
+            "http://purl.org/np/RAeyFN1C7zRfcPoEmGdwWHHgrE8vJHBONVgpq4JHDGyX0".to_string(),
+            "http://purl.org/np/RAz2LI_DNLC8GAJBKmaEzvgY5-pKlRC7Ph3mJ44LMzkEI".to_string(),
+        ]
+    );
+    let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
+    assert!(Nanopub::new(&np_rdf)?.references().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_resolve_np_urls() -> Result<(), Box<dyn Error>> {
+    let code = "RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw";
+    let urls = resolve_np_urls(code);
+    assert_eq!(urls.len(), LIST_SERVERS.len() + 1);
+    assert_eq!(urls[0], format!("{}{code}", LIST_SERVERS[0]));
+    assert_eq!(urls.last(), Some(&format!("https://w3id.org/np/{code}")));
+    // The given URI is tried first, and not duplicated
+    let urls = resolve_np_urls(&format!("https://w3id.org/np/{code}"));
+    assert_eq!(urls[0], format!("https://w3id.org/np/{code}"));
+    assert_eq!(urls.len(), LIST_SERVERS.len() + 1);
+    assert!(resolve_np_urls("not a nanopub").is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn fetch_by_artifact_code_uri() -> Result<(), Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/resources/signed.simple1-rsa.trig")?;
+    let code = "RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4";
+    let url = serve_once_at(&format!("np/{code}"), "200 OK", "application/trig", &np_rdf);
+    let np = Nanopub::fetch(&url).await?;
+    assert_eq!(np.info.trusty_hash, code);
+    assert_eq!(np.info.published, Some(url));
+    Ok(())
+}
+
+#[test]
+fn nanopub_builder() -> Result<(), Box<dyn Error>> {
+    let orcid = "https://orcid.org/0000-0000-0000-0000";
+    let claim = BlankNode::default();
+    let np = NanopubBuilder::new()
+        .add_assertion(claim.clone(), rdf::TYPE, NanopubBuilder::local("Claim"))
+        .add_assertion(claim, rdf::VALUE, Literal::new_simple_literal("42"))
+        .add_provenance(
+            NanopubBuilder::local("assertion"),
+            prov::WAS_ATTRIBUTED_TO,
+            NamedNodeRef::new(orcid)?,
+        )
+        .with_creator(orcid)
+        .with_created("2024-01-01T00:00:00Z".parse()?)
+        .with_label("The answer")
+        .with_type("http://example.org/Answer")
+        .with_prefix("ex", "http://example.org/")
+        .build()?;
+    assert_eq!(np.dataset.len(), 11);
+    assert!(np
+        .info
+        .prefixes
+        .contains(&("ex".to_string(), "http://example.org/".to_string())));
+    assert!(np
+        .dataset
+        .iter()
+        .any(|q| q.predicate == dct::CREATED && q.object.to_string().contains("2024-01-01")));
+    let profile = ProfileBuilder::new(get_test_key()).build()?;
+    let signed = np.sign(&profile)?;
+    assert!(!signed.info.trusty_hash.is_empty());
+    assert!(signed.rdf()?.contains("ex:Answer"));
+    // Validation fails without provenance about the assertion
+    assert!(NanopubBuilder::new()
+        .add_assertion(
+            NanopubBuilder::local("s"),
+            rdf::VALUE,
+            NanopubBuilder::local("o")
+        )
+        .with_creator(orcid)
+        .build()
+        .is_err());
+    assert!(NanopubBuilder::new()
+        .with_creator("not an iri")
+        .build()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_triple() -> Result<(), Box<dyn Error>> {
+    let (s, p, o) = parse_triple("_:claim", "<http://example.org/p>", "\"hello\"@en")?;
+    assert_eq!(s.to_string(), "_:claim");
+    assert_eq!(p.as_str(), "http://example.org/p");
+    assert_eq!(o.to_string(), "\"hello\"@en");
+    let (_, _, o) = parse_triple(
+        "http://example.org/s",
+        "http://example.org/p",
+        "http://example.org/o",
+    )?;
+    assert_eq!(o.to_string(), "<http://example.org/o>");
+    assert!(parse_triple(
+        "\"literal\"",
+        "http://example.org/p",
+        "http://example.org/o"
+    )
+    .is_err());
+    assert!(parse_triple("http://example.org/s", "not an iri", "http://example.org/o").is_err());
+    Ok(())
+}
+
+#[test]
+fn retraction() -> Result<(), Box<dyn Error>> {
+    let target = Nanopub::new(&fs::read_to_string(
+        "./tests/resources/signed.simple1-rsa.trig",
+    )?)?;
+    let orcid = "https://orcid.org/0000-0000-0000-0000".to_string();
+    let profile = ProfileBuilder::new(get_test_key())
+        .with_orcid(orcid.clone())
+        .build()?;
+    let retraction = Nanopub::new_retraction(target.info.uri.as_str(), &profile)?;
+    // Not signed yet
+    assert!(retraction.check_retraction(&target).is_err());
+    let retraction = retraction.sign(&profile)?;
+    retraction.check_retraction(&target)?;
+    assert!(retraction.rdf()?.contains("npx:retracts"));
+    // Retraction signed with another key
+    let (other_key, _) = gen_keys()?;
+    let other_profile = ProfileBuilder::new(other_key).with_orcid(orcid).build()?;
+    let other_retraction =
+        Nanopub::new_retraction(target.info.uri.as_str(), &other_profile)?.sign(&other_profile)?;
+    assert!(other_retraction.check_retraction(&target).is_err());
+    // Retraction of another nanopub
+    assert!(retraction.check_retraction(&other_retraction).is_err());
+    // Profile without ORCID
+    let no_orcid = ProfileBuilder::new(get_test_key()).build()?;
+    assert!(Nanopub::new_retraction(target.info.uri.as_str(), &no_orcid).is_err());
+    Ok(())
+}
+
+#[test]
+fn supersede() -> Result<(), Box<dyn Error>> {
+    let old_np = Nanopub::new(&fs::read_to_string(
+        "./tests/resources/signed.simple1-rsa.trig",
+    )?)?;
+    let profile = ProfileBuilder::new(get_test_key()).build()?;
+    let new_value = Literal::new_simple_literal("updated");
+    let new_np = old_np.supersede(&profile, |assertion| {
+        assertion.insert(TripleRef::new(
+            NamedNodeRef::new_unchecked("http://example.org/mosquito"),
+            rdf::VALUE,
+            &new_value,
+        ));
+    })?;
+    assert!(!new_np.info.trusty_hash.is_empty());
+    assert_ne!(new_np.info.trusty_hash, old_np.info.trusty_hash);
+    assert_eq!(new_np.info.public_key, old_np.info.public_key);
+    let assertion = new_np.dataset.graph(new_np.info.assertion.as_ref());
+    assert!(assertion.contains(TripleRef::new(
+        NamedNodeRef::new_unchecked("http://example.org/mosquito"),
+        rdf::VALUE,
+        &new_value,
+    )));
+    assert_eq!(assertion.len(), 2);
+    let new_np = Nanopub::new(&new_np.rdf()?)?.check()?;
+    let supersedes = |np: &Nanopub| -> Vec<String> {
+        np.dataset
+            .graph(np.info.pubinfo.as_ref())
+            .triples_for_predicate(npx::SUPERSEDES)
+            .map(|t| t.object.to_string())
+            .collect()
+    };
+    assert_eq!(
+        supersedes(&new_np),
+        vec![format!("<{}>", old_np.info.uri.as_str())]
+    );
+    // A new version of the new version only supersedes the previous one
+    let newer_np = new_np.supersede(&profile, |_| {})?;
+    assert_eq!(
+        supersedes(&newer_np),
+        vec![format!("<{}>", new_np.info.uri.as_str())]
+    );
+    // Refuse to supersede with another key, or an unsigned nanopub
+    let other_profile = ProfileBuilder::new(get_test_key())
+        .with_public_key("MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQC".to_string())
+        .build()?;
+    assert!(old_np.supersede(&other_profile, |_| {}).is_err());
+    let unsigned = Nanopub::new(&fs::read_to_string("./tests/resources/simple1-rsa.trig")?)?;
+    assert!(unsigned.supersede(&profile, |_| {}).is_err());
+    // The new version keeps a custom namespace
+    let options = SignOptions {
+        target_namespace: Some("https://example.org/np/".to_string()),
+        ..SignOptions::default()
+    };
+    let custom_np = unsigned.sign_with(&profile, &options)?;
+    let new_np = custom_np.supersede(&profile, |_| {})?;
+    assert!(new_np
+        .info
+        .uri
+        .as_str()
+        .starts_with("https://example.org/np/RA"));
+    assert_ne!(new_np.info.uri, custom_np.info.uri);
+    Nanopub::new(&new_np.rdf()?)?.check()?;
+    Ok(())
+}
+
+#[test]
+fn np_info_metadata() -> Result<(), Box<dyn Error>> {
+    let np = Nanopub::new(&fs::read_to_string(
+        "./tests/resources/signed.simple1-rsa.trig",
+    )?)?;
+    assert_eq!(
+        np.info.types,
+        vec!["http://purl.org/nanopub/x/ExampleNanopub"]
+    );
+    assert_eq!(
+        np.info.creators,
+        vec!["http://orcid.org/0000-0002-1267-0234"]
+    );
+    assert_eq!(
+        np.info.created.map(|d| d.to_rfc3339()),
+        Some("2014-07-24T17:05:11+00:00".to_string())
+    );
+    assert!(np.info.labels.is_empty() && np.info.retracts.is_empty());
+    // dct:created typed xsd:date is at midnight
+    for (date, expected) in [
+        ("2024-03-05", "2024-03-05T00:00:00+00:00"),
+        ("2024-03-05Z", "2024-03-05T00:00:00+00:00"),
+        ("2024-03-05+02:00", "2024-03-04T22:00:00+00:00"),
+    ] {
+        let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?.replace(
+            "\"2014-07-24T18:05:11+01:00\"^^xsd:dateTime",
+            &format!("\"{date}\"^^xsd:date"),
+        );
+        let np = Nanopub::new(&np_rdf)?;
+        assert_eq!(
+            np.info.created.map(|d| d.to_rfc3339()),
+            Some(expected.to_string()),
+            "{date}"
+        );
+    }
+    let unsigned = Nanopub::new(&fs::read_to_string("./tests/resources/simple1-rsa.trig")?)?;
+    assert_eq!(unsigned.info.types, np.info.types);
+    assert_eq!(unsigned.info.created, np.info.created);
+
+    let np = Nanopub::new(&fs::read_to_string(
+        "./tests/testsuite/valid/signed/workflow-1.trig",
+    )?)?;
+    assert_eq!(np.info.introduces.len(), 1);
+    assert!(np.info.introduces[0].ends_with("plan"));
+    // prov:generatedAtTime without timezone
+    assert_eq!(
+        np.info.created.map(|d| d.to_rfc3339()),
+        Some("2020-10-27T10:46:36.512175+00:00".to_string())
+    );
+
+    let np = Nanopub::new(&fs::read_to_string(
+        "./tests/testsuite/valid/trusty/fair-maturity-1.trig",
+    )?)?;
+    assert!(!np.info.authors.is_empty());
+
+    let profile = ProfileBuilder::new(get_test_key())
+        .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+        .build()?;
+    let target = "https://w3id.org/np/RAe_LF_8hl-wFdzgbxnLS2T3zNWwic2jFiF-tjuWCdkr4";
+    let retraction = Nanopub::new_retraction(target, &profile)?.sign(&profile)?;
+    assert_eq!(retraction.info.retracts, vec![target]);
+    assert_eq!(
+        retraction.info.creators,
+        vec!["https://orcid.org/0000-0000-0000-0000"]
+    );
+    let np = NanopubBuilder::new()
+        .add_assertion(
+            NanopubBuilder::local("s"),
+            rdf::VALUE,
+            NanopubBuilder::local("o"),
+        )
+        .add_provenance(
+            NanopubBuilder::local("assertion"),
+            rdf::VALUE,
+            NanopubBuilder::local("o"),
+        )
+        .with_label("A label")
+        .with_type("http://example.org/Type")
+        .build()?;
+    assert_eq!(np.info.labels, vec!["A label"]);
+    assert_eq!(np.info.types, vec!["http://example.org/Type"]);
+    let info = serde_json::to_value(&retraction.info)?;
+    assert_eq!(info["retracts"][0], target);
+    assert!(info["created"].is_string());
+    Ok(())
+}
+
+#[test]
+fn sign_reproducible() -> Result<(), Box<dyn Error>> {
+    let profile = ProfileBuilder::new(get_test_key())
+        .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+        .build()?;
+    let created: DateTime<Utc> = "2024-01-01T00:00:00Z".parse()?;
+    for file in [
+        "./tests/resources/nanopub_test_blank.trig",
+        "./tests/testsuite/valid/plain/simple1.trig",
+    ] {
+        let np_rdf = fs::read_to_string(file)?;
+        let first = Nanopub::new(&np_rdf)?.sign_with_clock(&profile, || created)?;
+        let second = Nanopub::new(&np_rdf)?.sign_with_clock(&profile, || created)?;
+        assert_eq!(first.rdf()?, second.rdf()?);
+    }
+    // The clock is only used when the creation date is not already set
+    let np_rdf = fs::read_to_string("./tests/resources/nanopub_test_blank.trig")?;
+    let np = Nanopub::new(&np_rdf)?.sign_with_clock(&profile, || created)?;
+    assert_eq!(np.info.created, Some(created));
+    let later: DateTime<Utc> = "2025-06-01T00:00:00Z".parse()?;
+    let resigned = np.unsign()?.sign_with_clock(&profile, || later)?;
+    assert_eq!(resigned.info.created, Some(created));
+    Ok(())
+}
+
+#[test]
+fn sign_with_options() -> Result<(), Box<dyn Error>> {
+    let profile = ProfileBuilder::new(get_test_key())
+        .with_orcid("https://orcid.org/0000-0000-0000-0000".to_string())
+        .build()?;
+    let np_rdf = fs::read_to_string("./tests/resources/nanopub_test_blank.trig")?;
+    let options = SignOptions {
+        add_created: false,
+        creator_predicate: Some(pav::CREATED_BY.into_owned()),
+        allow_resign: false,
+        signature_name: "signature".to_string(),
+        target_namespace: Some("https://example.org/np/".to_string()),
+        ..SignOptions::default()
+    };
+    let np = Nanopub::new(&np_rdf)?.sign_with(&profile, &options)?;
+    assert!(np
+        .info
+        .uri
+        .as_str()
+        .starts_with("https://example.org/np/RA"));
+    assert_eq!(np.info.created, None);
+    assert_eq!(
+        np.info.creators,
+        vec!["https://orcid.org/0000-0000-0000-0000".to_string()]
+    );
+    let pubinfo = GraphNameRef::from(np.info.pubinfo.as_ref());
+    assert!(!np
+        .dataset
+        .quads_for_predicate(dct::CREATOR)
+        .any(|q| q.graph_name == pubinfo));
+    assert!(np
+        .dataset
+        .quads_for_predicate(npx::HAS_SIGNATURE)
+        .any(|q| q.subject.to_string().ends_with("/signature>")));
+    let checked = Nanopub::new(&np.rdf()?)?.check()?;
+    assert_eq!(checked.info.uri, np.info.uri);
+    // Re-signing is not allowed
+    assert!(np.sign_with(&profile, &options).is_err());
+    // Target namespace must end with a separator
+    let options = SignOptions {
+        target_namespace: Some("https://example.org/np".to_string()),
+        ..SignOptions::default()
+    };
+    assert!(Nanopub::new(&np_rdf)?
+        .sign_with(&profile, &options)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn graph_accessors() -> Result<(), Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/resources/simple1-rsa.trig")?;
+    let mut np = Nanopub::new(&np_rdf)?;
+    assert_eq!(np.assertion().len(), 1);
+    assert_eq!(np.provenance().len(), 1);
+    assert_eq!(np.graph(NpPart::Head).len(), 4);
+    assert_eq!(np.graph_iri(NpPart::PubInfo), &np.info.pubinfo);
+    let pubinfo_len = np.pubinfo().len();
+
+    // Adding a triple updates the nanopub info
+    let target =
+        NamedNodeRef::new("https://w3id.org/np/RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw")?;
+    let orcid = NamedNodeRef::new("https://orcid.org/0000-0000-0000-0000")?;
+    let retracts = TripleRef::new(orcid, npx::RETRACTS, target);
+    assert!(np.add_triple(NpPart::Assertion, retracts)?);
+    assert!(!np.add_triple(NpPart::Assertion, retracts)?);
+    assert_eq!(np.assertion().len(), 2);
+    assert_eq!(np.info.retracts, vec![target.as_str().to_string()]);
+    assert!(np.remove_triple(NpPart::Assertion, retracts)?);
+    assert!(!np.remove_triple(NpPart::Assertion, retracts)?);
+    assert!(np.info.retracts.is_empty());
+
+    let label = Literal::new_simple_literal("A nanopub");
+    let np_node = np.info.uri.clone();
+    np.add_triple(
+        NpPart::PubInfo,
+        TripleRef::new(&np_node, rdfs::LABEL, &label),
+    )?;
+    assert_eq!(np.pubinfo().len(), pubinfo_len + 1);
+    assert_eq!(np.info.labels, vec!["A nanopub".to_string()]);
+    assert!(np.verify()?.valid);
+
+    // Changes breaking the head graph are reverted
+    let head_triple = np
+        .graph(NpPart::Head)
+        .triples_for_predicate(oxrdf::NamedNodeRef::new(
+            "http://www.nanopub.org/nschema#hasAssertion",
+        )?)
+        .next()
+        .map(|t| t.into_owned())
+        .unwrap();
+    assert!(np.remove_triple(NpPart::Head, &head_triple).is_err());
+    assert_eq!(np.graph(NpPart::Head).len(), 4);
+    Ok(())
+}
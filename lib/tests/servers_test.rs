@@ -0,0 +1,65 @@
+use nanopub::{
+    constants::LIST_SERVERS,
+    get_np_server,
+    servers::{load_servers_from_file, parse_servers, rank_servers, set_np_servers, NpServer},
+};
+use std::error::Error;
+
+#[test]
+fn load_and_rank_servers() -> Result<(), Box<dyn Error>> {
+    let servers = load_servers_from_file("./tests/resources/servers.json")?;
+    // Only registries are kept
+    assert_eq!(servers.len(), 3);
+    let ranked = rank_servers(servers.clone());
+    assert_eq!(ranked[0].url, "https://registry.fast.example.org");
+    assert_eq!(ranked[1].url, "https://registry.slow.example.org/");
+    assert!(!ranked[2].is_healthy());
+
+    // Servers provided in the monitor output feed get_np_server
+    set_np_servers(servers)?;
+    assert_eq!(get_np_server(false), "https://registry.fast.example.org/");
+    for _ in 0..10 {
+        assert_ne!(get_np_server(true), "https://registry.down.example.org/");
+    }
+    // Random servers are picked among the best ranked
+    let servers: Vec<NpServer> = (1..=5)
+        .map(|i| NpServer {
+            response_time: Some(i as f64 * 100.0),
+            ..NpServer::new(&format!("https://registry{i}.example.org/"))
+        })
+        .collect();
+    set_np_servers(servers)?;
+    for _ in 0..20 {
+        let server = get_np_server(true);
+        assert!(
+            !server.contains("registry4") && !server.contains("registry5"),
+            "{server}"
+        );
+    }
+    // Fallback to the default list when no healthy server is provided
+    set_np_servers(vec![])?;
+    assert_eq!(get_np_server(false), LIST_SERVERS[0]);
+    Ok(())
+}
+
+#[test]
+fn parse_servers_list() -> Result<(), Box<dyn Error>> {
+    let servers =
+        parse_servers("# Servers\nhttps://registry.example.org/\n\nhttps://other.example.org/\n")?;
+    assert_eq!(
+        servers,
+        vec![
+            NpServer::new("https://registry.example.org/"),
+            NpServer::new("https://other.example.org/")
+        ]
+    );
+    let servers = parse_servers(r#"[{"url": "https://registry.example.org/"}]"#)?;
+    assert_eq!(
+        servers,
+        vec![NpServer::new("https://registry.example.org/")]
+    );
+    assert!(parse_servers("[wrong").is_err());
+    // Entries without url are not part of the monitor output
+    assert!(parse_servers(r#"[{"serverUrl": "https://registry.example.org/"}]"#).is_err());
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use nanopub::{
+    template::Template,
+    vocab::{npx, nt},
+    Nanopub, NanopubBuilder, ProfileBuilder,
+};
+use oxrdf::{vocab::rdfs, Literal, NamedNodeRef, TripleRef};
+use std::{collections::HashMap, error::Error, fs};
+
+const ORCID: &str = "https://orcid.org/0000-0000-0000-0000";
+
+fn load_template(file: &str) -> Result<Template, Box<dyn Error>> {
+    let np_rdf = fs::read_to_string(format!("./tests/testsuite/valid/signed/{file}"))?;
+    Ok(Template::new(Nanopub::new(&np_rdf)?)?)
+}
+
+fn values(values: &[(&str, &str)]) -> HashMap<String, String> {
+    values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn template_placeholders() -> Result<(), Box<dyn Error>> {
+    let template = load_template("nanobench_hasRead-template-v5.trig")?;
+    assert_eq!(
+        template.label.as_deref(),
+        Some("Announcing a paper that I have read")
+    );
+    let names: Vec<&str> = template
+        .placeholders
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["comment", "paper"]);
+    let paper = template.placeholder("paper").unwrap();
+    assert_eq!(paper.prefix.as_deref(), Some("https://doi.org/"));
+    assert_eq!(paper.regex.as_deref(), Some("10.(\\d)+/(\\S)+"));
+    assert!(!paper.is_literal());
+    assert!(template.placeholder("comment").unwrap().is_literal());
+    assert_eq!(template.statements.len(), 2);
+    assert!(!template.statements[0].optional);
+    assert!(template.statements[1].optional);
+    assert!(template.is_optional(template.placeholder("comment").unwrap()));
+    assert!(!template.is_optional(paper));
+
+    let template = load_template("nanobench_new-individual-template-v3.trig")?;
+    let individual = template.placeholder("individual").unwrap();
+    assert!(individual.is_local() && individual.is_introduced());
+    assert_eq!(
+        template.placeholder("class").unwrap().possible_values,
+        vec!["http://www.w3.org/2002/07/owl#Thing".to_string()]
+    );
+    assert_eq!(
+        template
+            .statements
+            .iter()
+            .map(|st| st.order)
+            .collect::<Vec<_>>(),
+        vec![Some(0), Some(1), Some(2), Some(3)]
+    );
+    Ok(())
+}
+
+#[test]
+fn template_fill() -> Result<(), Box<dyn Error>> {
+    let template = load_template("nanobench_hasRead-template-v5.trig")?;
+    let np = template.fill(&values(&[("paper", "10.1038/sdata.2016.18")]), ORCID)?;
+    // The optional statement without value is skipped
+    assert_eq!(np.assertion().len(), 1);
+    assert!(np.assertion().contains(TripleRef::new(
+        NamedNodeRef::new(ORCID)?,
+        NamedNodeRef::new("http://purl.org/petapico/o/paperclub#hasRead")?,
+        NamedNodeRef::new("https://doi.org/10.1038/sdata.2016.18")?,
+    )));
+    assert!(np.pubinfo().contains(TripleRef::new(
+        &NanopubBuilder::uri(),
+        nt::WAS_CREATED_FROM_TEMPLATE,
+        &template.np.info.uri,
+    )));
+    assert_eq!(np.info.creators, vec![ORCID.to_string()]);
+    assert!(np.info.signature.is_empty());
+
+    // The value can also be given with its prefix
+    let np = template.fill(
+        &values(&[
+            ("paper", "https://doi.org/10.1038/sdata.2016.18"),
+            ("comment", "Great paper"),
+        ]),
+        ORCID,
+    )?;
+    assert_eq!(np.assertion().len(), 2);
+    assert!(np.assertion().contains(TripleRef::new(
+        NamedNodeRef::new("https://doi.org/10.1038/sdata.2016.18")?,
+        rdfs::COMMENT,
+        &Literal::new_simple_literal("Great paper"),
+    )));
+    Ok(())
+}
+
+#[test]
+fn template_fill_local_resource() -> Result<(), Box<dyn Error>> {
+    let template = load_template("nanobench_new-individual-template-v3.trig")?;
+    let np = template.fill(
+        &values(&[
+            ("individual", "mosquito"),
+            ("class", "http://www.w3.org/2002/07/owl#Thing"),
+            ("name", "Mosquito"),
+            ("description", "A small flying insect"),
+        ]),
+        ORCID,
+    )?;
+    assert_eq!(np.assertion().len(), 3);
+    let individual = np.info.ns.get("mosquito");
+    assert!(np.assertion().contains(TripleRef::new(
+        &individual,
+        rdfs::LABEL,
+        &Literal::new_simple_literal("Mosquito"),
+    )));
+    assert!(np.pubinfo().contains(TripleRef::new(
+        &NanopubBuilder::uri(),
+        npx::INTRODUCES,
+        &individual
+    )));
+    assert_eq!(np.info.introduces, vec![individual.as_str().to_string()]);
+
+    // Local resources get the trusty namespace of the nanopub when signing
+    let profile = ProfileBuilder::new(fs::read_to_string("./tests/resources/id_rsa")?).build()?;
+    let np = np.sign(&profile)?;
+    assert_eq!(
+        np.info.introduces,
+        vec![np.info.ns.get("mosquito").as_str().to_string()]
+    );
+    Ok(())
+}
+
+#[test]
+fn template_fill_errors() -> Result<(), Box<dyn Error>> {
+    let template = load_template("nanobench_hasRead-template-v5.trig")?;
+    // Missing required value
+    assert!(template
+        .fill(&values(&[("comment", "Great paper")]), ORCID)
+        .is_err());
+    // Value not matching the regex
+    assert!(template
+        .fill(&values(&[("paper", "not-a-doi")]), ORCID)
+        .is_err());
+    // Unknown placeholder
+    assert!(template
+        .fill(
+            &values(&[("paper", "10.1038/sdata.2016.18"), ("unknown", "value")]),
+            ORCID
+        )
+        .is_err());
+    // Only assertion templates can be filled
+    assert!(load_template("nanobench_somebodyElse-prtemplate.trig").is_err());
+    Ok(())
+}
+
+fn load_grouped_template() -> Result<Template, Box<dyn Error>> {
+    let np_rdf = fs::read_to_string("./tests/resources/template-grouped.trig")?;
+    Ok(Template::new(Nanopub::new(&np_rdf)?)?)
+}
+
+#[test]
+fn template_grouped_statements() -> Result<(), Box<dyn Error>> {
+    let template = load_grouped_template()?;
+    // The statements of a group follow each other, and are optional like their group
+    assert_eq!(template.statements.len(), 5);
+    assert!(template.statements[0].group.is_none());
+    assert!(template.statements[1].group.is_some());
+    assert_eq!(template.statements[1].group, template.statements[2].group);
+    assert!(template.statements[1].optional && template.statements[2].optional);
+    assert!(template.is_optional(template.placeholder("authorName").unwrap()));
+
+    // The group is skipped when one of its values is missing
+    let np = template.fill(
+        &values(&[
+            ("document", "report"),
+            ("status", "http://example.org/Final"),
+            ("author", ORCID),
+        ]),
+        ORCID,
+    )?;
+    assert_eq!(np.assertion().len(), 1);
+    let np = template.fill(
+        &values(&[
+            ("document", "report"),
+            ("status", "http://example.org/Final"),
+            ("author", ORCID),
+            ("authorName", "Jane Doe"),
+        ]),
+        ORCID,
+    )?;
+    assert_eq!(np.assertion().len(), 3);
+    assert!(np.assertion().contains(TripleRef::new(
+        NamedNodeRef::new(ORCID)?,
+        rdfs::LABEL,
+        &Literal::new_simple_literal("Jane Doe"),
+    )));
+    Ok(())
+}
+
+#[test]
+fn template_fill_invalid_values() -> Result<(), Box<dyn Error>> {
+    let template = load_grouped_template()?;
+    let fill = |extra: &[(&str, &str)]| {
+        let mut values = values(&[
+            ("document", "report"),
+            ("status", "http://example.org/Draft"),
+        ]);
+        values.extend(extra.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        template.fill(&values, ORCID)
+    };
+    assert!(fill(&[]).is_ok());
+    // Local resources are local names
+    assert!(fill(&[("document", "my report")]).is_err());
+    assert!(fill(&[("document", "reports/report")]).is_err());
+    // URI placeholders are IRIs
+    assert!(fill(&[("link", "not an IRI")]).is_err());
+    assert!(fill(&[("link", "https://example.org/report")]).is_ok());
+    // Restricted choices are one of the possible values
+    assert!(fill(&[("status", "http://example.org/Other")]).is_err());
+    // Possible values that are not in the template are not supported
+    let error = fill(&[("kind", "http://example.org/Report")]).unwrap_err();
+    assert!(
+        error.to_string().contains("Unsupported template"),
+        "{error}"
+    );
+    Ok(())
+}
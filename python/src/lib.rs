@@ -24,6 +24,8 @@ fn nanopub_sign(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<NpProfilePy>()?;
     m.add_class::<NanopubPy>()?;
     m.add_class::<KeyPair>()?;
+    m.add_class::<NpCachePy>()?;
+    m.add_class::<NanopubBuilderPy>()?;
     m.add_wrapped(wrap_pyfunction!(get_np_server))?;
     // m.add_function(wrap_pyfunction!(sum_as_string, m))?;
     Ok(())
@@ -1,6 +1,14 @@
-use nanopub::{get_np_server as get_server, profile::gen_keys, Nanopub, NpProfile, ProfileBuilder};
+use nanopub::{
+    builder::parse_triple,
+    cache::{MemoryCache, NpCache},
+    get_np_server as get_server,
+    nanopub::NpPart,
+    profile::gen_keys,
+    Nanopub, NanopubBuilder, NanopubStore, NpProfile, ProfileBuilder,
+};
 use pyo3::{exceptions::PyException, prelude::*, pymethods};
 use pythonize::pythonize;
+use std::sync::Arc;
 // use pyo3::types::IntoPyDict;
 // use pyo3_asyncio::generic::future_into_py;
 use tokio::runtime::Runtime;
@@ -44,6 +52,39 @@ impl NanopubPy {
             .map_err(|e| PyErr::new::<PyException, _>(format!("Error checking: {e}")))
     }
 
+    #[pyo3()]
+    fn head(&self) -> Vec<(String, String, String)> {
+        self.triples(NpPart::Head)
+    }
+
+    #[pyo3()]
+    fn assertion(&self) -> Vec<(String, String, String)> {
+        self.triples(NpPart::Assertion)
+    }
+
+    #[pyo3()]
+    fn provenance(&self) -> Vec<(String, String, String)> {
+        self.triples(NpPart::Provenance)
+    }
+
+    #[pyo3()]
+    fn pubinfo(&self) -> Vec<(String, String, String)> {
+        self.triples(NpPart::PubInfo)
+    }
+
+    #[pyo3()]
+    fn verify(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let result = self
+            .np
+            .verify()
+            .map_err(|e| PyErr::new::<PyException, _>(format!("Error checking: {e}")))?;
+        pythonize(py, &result)
+            .map(|bound| bound.into())
+            .map_err(|e| {
+                PyErr::new::<PyException, _>(format!("Error converting check result to dict: {e}"))
+            })
+    }
+
     #[pyo3(signature = (profile))]
     fn sign(&self, profile: &NpProfilePy) -> PyResult<Self> {
         self.np
@@ -102,14 +143,17 @@ impl NanopubPy {
     }
 
     #[staticmethod]
-    #[pyo3(signature = (uri))]
-    fn fetch(uri: &str) -> PyResult<Self> {
+    #[pyo3(signature = (uri, cache=None))]
+    fn fetch(uri: &str, cache: Option<&NpCachePy>) -> PyResult<Self> {
         let rt = Runtime::new()
             .map_err(|e| PyErr::new::<PyException, _>(format!("Runtime failed: {e}")))?;
         let result = rt.block_on(async move {
-            Nanopub::fetch(uri)
-                .await
-                .map_err(|e| PyErr::new::<PyException, _>(format!("Error fetching: {e}")))
+            if let Some(cache) = cache {
+                Nanopub::fetch_cached(uri, cache.cache.as_ref()).await
+            } else {
+                Nanopub::fetch(uri).await
+            }
+            .map_err(|e| PyErr::new::<PyException, _>(format!("Error fetching: {e}")))
         });
         result.map(|np| Self { np })
     }
@@ -191,6 +235,23 @@ impl NanopubPy {
     // }
 }
 
+impl NanopubPy {
+    /// Triples of a graph of the nanopub, with terms in N-Triples syntax
+    fn triples(&self, part: NpPart) -> Vec<(String, String, String)> {
+        self.np
+            .graph(part)
+            .iter()
+            .map(|t| {
+                (
+                    t.subject.to_string(),
+                    t.predicate.to_string(),
+                    t.object.to_string(),
+                )
+            })
+            .collect()
+    }
+}
+
 #[pyclass(name = "NpProfile", module = "nanopub_sign", from_py_object)]
 #[derive(Clone)]
 pub struct NpProfilePy {
@@ -227,6 +288,143 @@ impl NpProfilePy {
     }
 }
 
+/// Cache of trusty nanopubs used when fetching, on disk if a directory is provided, in memory otherwise
+#[pyclass(name = "NpCache", module = "nanopub_sign", from_py_object)]
+#[derive(Clone)]
+pub struct NpCachePy {
+    cache: Arc<dyn NpCache>,
+}
+
+#[pymethods]
+impl NpCachePy {
+    #[new]
+    #[pyo3(signature = (directory=None, capacity=1000))]
+    fn new(directory: Option<String>, capacity: usize) -> PyResult<Self> {
+        let cache: Arc<dyn NpCache> = if let Some(directory) = directory {
+            Arc::new(NanopubStore::new(directory).map_err(|e| {
+                PyErr::new::<PyException, _>(format!("Error opening the cache directory: {e}"))
+            })?)
+        } else {
+            Arc::new(MemoryCache::new(capacity))
+        };
+        Ok(Self { cache })
+    }
+}
+
+/// Build a nanopub, terms are given as IRIs, blank nodes `_:id`, or N-Triples literals such as `"label"@en`
+#[pyclass(name = "NanopubBuilder", module = "nanopub_sign", from_py_object)]
+#[derive(Clone)]
+pub struct NanopubBuilderPy {
+    builder: NanopubBuilder,
+}
+
+#[pymethods]
+impl NanopubBuilderPy {
+    #[new]
+    #[pyo3(signature = ())]
+    fn new() -> Self {
+        Self {
+            builder: NanopubBuilder::new(),
+        }
+    }
+
+    /// IRI with the given local name in the temporary namespace of the nanopub
+    #[staticmethod]
+    #[pyo3(signature = (name))]
+    fn local(name: &str) -> String {
+        NanopubBuilder::local(name).into_string()
+    }
+
+    #[pyo3(signature = (subject, predicate, object))]
+    fn add_assertion<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        subject: &str,
+        predicate: &str,
+        object: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let (s, p, o) = parse_triple(subject, predicate, object)
+            .map_err(|e| PyErr::new::<PyException, _>(format!("{e}")))?;
+        slf.builder = std::mem::take(&mut slf.builder).add_assertion(s, p, o);
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (subject, predicate, object))]
+    fn add_provenance<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        subject: &str,
+        predicate: &str,
+        object: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let (s, p, o) = parse_triple(subject, predicate, object)
+            .map_err(|e| PyErr::new::<PyException, _>(format!("{e}")))?;
+        slf.builder = std::mem::take(&mut slf.builder).add_provenance(s, p, o);
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (subject, predicate, object))]
+    fn add_pubinfo<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        subject: &str,
+        predicate: &str,
+        object: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let (s, p, o) = parse_triple(subject, predicate, object)
+            .map_err(|e| PyErr::new::<PyException, _>(format!("{e}")))?;
+        slf.builder = std::mem::take(&mut slf.builder).add_pubinfo(s, p, o);
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (creator))]
+    fn with_creator<'py>(mut slf: PyRefMut<'py, Self>, creator: &str) -> PyRefMut<'py, Self> {
+        slf.builder = std::mem::take(&mut slf.builder).with_creator(creator);
+        slf
+    }
+
+    /// Set the creation date, as an ISO 8601 string, e.g. `2024-01-01T00:00:00Z`
+    #[pyo3(signature = (created))]
+    fn with_created<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        created: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let created = created.parse().map_err(|e| {
+            PyErr::new::<PyException, _>(format!("Invalid creation date {created}: {e}"))
+        })?;
+        slf.builder = std::mem::take(&mut slf.builder).with_created(created);
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (label))]
+    fn with_label<'py>(mut slf: PyRefMut<'py, Self>, label: &str) -> PyRefMut<'py, Self> {
+        slf.builder = std::mem::take(&mut slf.builder).with_label(label);
+        slf
+    }
+
+    #[pyo3(signature = (np_type))]
+    fn with_type<'py>(mut slf: PyRefMut<'py, Self>, np_type: &str) -> PyRefMut<'py, Self> {
+        slf.builder = std::mem::take(&mut slf.builder).with_type(np_type);
+        slf
+    }
+
+    #[pyo3(signature = (prefix, namespace))]
+    fn with_prefix<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        prefix: &str,
+        namespace: &str,
+    ) -> PyRefMut<'py, Self> {
+        slf.builder = std::mem::take(&mut slf.builder).with_prefix(prefix, namespace);
+        slf
+    }
+
+    #[pyo3()]
+    fn build(&self) -> PyResult<NanopubPy> {
+        self.builder
+            .clone()
+            .build()
+            .map(|np| NanopubPy { np })
+            .map_err(|e| PyErr::new::<PyException, _>(format!("Error building nanopub: {e}")))
+    }
+}
+
 #[pyclass(name = "KeyPair", module = "nanopub_sign", from_py_object)]
 #[derive(Clone)]
 pub struct KeyPair {
@@ -251,5 +449,5 @@ impl KeyPair {
 #[pyfunction]
 #[pyo3(signature = (random=true))]
 pub fn get_np_server(random: Option<bool>) -> PyResult<String> {
-    Ok(get_server(random.unwrap_or(true)).to_string())
+    Ok(get_server(random.unwrap_or(true)))
 }
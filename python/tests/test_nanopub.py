@@ -1,5 +1,5 @@
 import pytest
-from nanopub_sign import KeyPair, Nanopub, NpProfile, get_np_server
+from nanopub_sign import KeyPair, Nanopub, NanopubBuilder, NpCache, NpProfile, get_np_server
 
 rdf_str = """@prefix : <http://purl.org/nanopub/temp/mynanopub#> .
 @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
@@ -45,6 +45,28 @@ def test_check():
     assert not np.info()["trusty_hash"]
     assert not np.info()["published"]
 
+def test_verify():
+    result = Nanopub(rdf_str).sign(profile).verify()
+    assert result["valid"]
+    assert result["trusty"]
+    assert result["signed"]
+    assert result["algorithm"] == "RSA"
+    assert result["error"] is None
+
+def test_graphs():
+    np = Nanopub(rdf_str)
+    assert np.assertion() == [("<http://example.org/mosquito>", "<http://example.org/transmits>", "<http://example.org/malaria>")]
+    assert len(np.head()) == 4
+    assert len(np.provenance()) == 1
+    assert any(p == "<http://purl.org/dc/terms/created>" for _, p, _ in np.pubinfo())
+
+def test_info():
+    info = Nanopub(rdf_str).info()
+    assert info["types"] == ["http://purl.org/nanopub/x/ExampleNanopub"]
+    assert info["creators"] == ["http://orcid.org/0000-0002-1267-0234"]
+    assert info["created"].startswith("2014-07-24T17:05:11")
+    assert info["retracts"] == []
+
 def test_sign():
     np = Nanopub(rdf_str)
     signed = np.sign(profile=profile)
@@ -78,3 +100,27 @@ def test_fetch():
     np = Nanopub.fetch("https://w3id.org/np/RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw")
     assert np.info()["trusty_hash"]
     assert np.info()["published"]
+
+def test_fetch_cached(tmp_path):
+    uri = "https://w3id.org/np/RAltRkGOtHoj5LcBJZ62AMVOAVc0hnxt45LMaCXgxJ4fw"
+    cache = NpCache(directory=str(tmp_path))
+    np = Nanopub.fetch(uri, cache=cache)
+    cached = Nanopub.fetch(uri, cache=cache)
+    assert np.info()["trusty_hash"] == cached.info()["trusty_hash"]
+    assert cached.info()["published"]
+
+def test_builder():
+    orcid = "https://orcid.org/0000-0000-0000-0000"
+    np = (
+        NanopubBuilder()
+        .add_assertion("_:claim", "http://www.w3.org/1999/02/22-rdf-syntax-ns#value", '"42"')
+        .add_provenance(NanopubBuilder.local("assertion"), "http://www.w3.org/ns/prov#wasAttributedTo", orcid)
+        .with_creator(orcid)
+        .with_created("2024-01-01T00:00:00Z")
+        .with_label("The answer")
+        .build()
+    )
+    signed = np.sign(profile=profile)
+    assert signed.info()["trusty_hash"]
+    with pytest.raises(Exception):
+        NanopubBuilder().with_creator(orcid).build()